@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rig::vector_store::math::fused_cosine_similarity;
+
+const CANDIDATES: usize = 1_000;
+
+/// Reference implementation computing the dot product and magnitudes in separate passes
+fn naive_cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let magnitude1 = a.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
+    let magnitude2 = b.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
+    dot / (magnitude1 * magnitude2)
+}
+
+fn vectors(ndims: usize) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let query = (0..ndims).map(|i| (i as f64 * 0.37).sin()).collect();
+    let candidates = (0..CANDIDATES)
+        .map(|n| (0..ndims).map(|i| ((i + n) as f64 * 0.11).cos()).collect())
+        .collect();
+    (query, candidates)
+}
+
+fn bench_top_n_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cosine_similarity_scan");
+
+    for ndims in [384, 1536, 3072] {
+        let (query, candidates) = vectors(ndims);
+
+        group.bench_with_input(BenchmarkId::new("naive", ndims), &ndims, |b, _| {
+            b.iter(|| {
+                candidates
+                    .iter()
+                    .map(|c| naive_cosine_similarity(black_box(&query), black_box(c)))
+                    .fold(f64::MIN, f64::max)
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("fused", ndims), &ndims, |b, _| {
+            b.iter(|| {
+                candidates
+                    .iter()
+                    .map(|c| fused_cosine_similarity(black_box(&query), black_box(c)))
+                    .fold(f64::MIN, f64::max)
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_top_n_scan);
+criterion_main!(benches);
@@ -106,7 +106,7 @@
 //! let response = agent.prompt("What does \"glarb-glarb\" mean?").await
 //!     .expect("Failed to prompt the agent");
 //! ```
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use futures::{stream, StreamExt, TryStreamExt};
 
@@ -160,31 +160,111 @@ pub struct Agent<M: CompletionModel> {
     dynamic_tools: Vec<(usize, Box<dyn VectorStoreIndexDyn>)>,
     /// Actual tool implementations
     pub tools: ToolSet,
+    /// Metadata attached to every completion request of the agent
+    metadata: BTreeMap<String, String>,
 }
 
-impl<M: CompletionModel> Completion<M> for Agent<M> {
-    async fn completion(
+/// A context document that was provided to the agent when prompted.
+/// Returned by [Agent::prompt_with_citations] so that callers can attribute the answer to its sources.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CitedSource {
+    /// Id of the document (e.g.: the id of the document in the vector store)
+    pub id: String,
+    /// Text of the document as it was provided to the model
+    pub text: String,
+    /// Similarity score of the document to the prompt.
+    /// `None` for static context documents.
+    pub score: Option<f64>,
+}
+
+impl<M: CompletionModel> Agent<M> {
+    /// Prompt the agent and return its answer along with the context documents
+    /// (both static and dynamic) that were provided to the model.
+    ///
+    /// # Example
+    /// ```rust
+    /// let (answer, sources) = agent.prompt_with_citations("What does \"glarb-glarb\" mean?").await?;
+    ///
+    /// for source in sources {
+    ///     println!("{} ({:?})", source.id, source.score);
+    /// }
+    /// ```
+    pub async fn prompt_with_citations(
         &self,
         prompt: &str,
-        chat_history: Vec<Message>,
-    ) -> Result<CompletionRequestBuilder<M>, CompletionError> {
-        let dynamic_context = stream::iter(self.dynamic_context.iter())
+    ) -> Result<(String, Vec<CitedSource>), PromptError> {
+        let dynamic_context = self.fetch_dynamic_context(prompt).await?;
+
+        let sources = self
+            .static_context
+            .iter()
+            .map(|doc| CitedSource {
+                id: doc.id.clone(),
+                text: doc.text.clone(),
+                score: None,
+            })
+            .chain(dynamic_context.iter().map(|(score, doc)| CitedSource {
+                id: doc.id.clone(),
+                text: doc.text.clone(),
+                score: Some(*score),
+            }))
+            .collect();
+
+        let dynamic_context = dynamic_context.into_iter().map(|(_, doc)| doc).collect();
+
+        let answer = self
+            .send_and_resolve(
+                self.completion_with_context(prompt, vec![], dynamic_context)
+                    .await?,
+            )
+            .await?;
+
+        Ok((answer, sources))
+    }
+
+    /// Send a completion request and resolve the model's choice: messages are returned as is,
+    /// and tool calls are executed and their output returned.
+    async fn send_and_resolve(
+        &self,
+        request: CompletionRequestBuilder<M>,
+    ) -> Result<String, PromptError> {
+        match request.send().await? {
+            CompletionResponse {
+                choice: ModelChoice::Message(msg),
+                ..
+            } => Ok(msg),
+            CompletionResponse {
+                choice: ModelChoice::ToolCall(toolname, args),
+                ..
+            } => Ok(self.tools.call(&toolname, args.to_string()).await?),
+        }
+    }
+
+    /// Fetch the dynamic context documents for the given prompt, along with their scores
+    async fn fetch_dynamic_context(
+        &self,
+        prompt: &str,
+    ) -> Result<Vec<(f64, Document)>, CompletionError> {
+        stream::iter(self.dynamic_context.iter())
             .then(|(num_sample, index)| async {
                 Ok::<_, VectorStoreError>(
                     index
                         .top_n(prompt, *num_sample)
                         .await?
                         .into_iter()
-                        .map(|(_, id, doc)| {
+                        .map(|(score, id, doc)| {
                             // Pretty print the document if possible for better readability
                             let text = serde_json::to_string_pretty(&doc)
                                 .unwrap_or_else(|_| doc.to_string());
 
-                            Document {
-                                id,
-                                text,
-                                additional_props: HashMap::new(),
-                            }
+                            (
+                                score,
+                                Document {
+                                    id,
+                                    text,
+                                    additional_props: HashMap::new(),
+                                },
+                            )
                         })
                         .collect::<Vec<_>>(),
                 )
@@ -194,8 +274,16 @@ impl<M: CompletionModel> Completion<M> for Agent<M> {
                 Ok(acc)
             })
             .await
-            .map_err(|e| CompletionError::RequestError(Box::new(e)))?;
+            .map_err(|e| CompletionError::RequestError(Box::new(e)))
+    }
 
+    /// Build a completion request using the given dynamic context documents
+    async fn completion_with_context(
+        &self,
+        prompt: &str,
+        chat_history: Vec<Message>,
+        dynamic_context: Vec<Document>,
+    ) -> Result<CompletionRequestBuilder<M>, CompletionError> {
         let dynamic_tools = stream::iter(self.dynamic_tools.iter())
             .then(|(num_sample, index)| async {
                 Ok::<_, VectorStoreError>(
@@ -232,9 +320,12 @@ impl<M: CompletionModel> Completion<M> for Agent<M> {
             .collect::<Vec<_>>()
             .await;
 
-        Ok(self
-            .model
-            .completion_request(prompt)
+        let request = self.metadata.iter().fold(
+            self.model.completion_request(prompt),
+            |request, (key, value)| request.metadata(key, value),
+        );
+
+        Ok(request
             .preamble(self.preamble.clone())
             .messages(chat_history)
             .documents([self.static_context.clone(), dynamic_context].concat())
@@ -245,6 +336,24 @@ impl<M: CompletionModel> Completion<M> for Agent<M> {
     }
 }
 
+impl<M: CompletionModel> Completion<M> for Agent<M> {
+    async fn completion(
+        &self,
+        prompt: &str,
+        chat_history: Vec<Message>,
+    ) -> Result<CompletionRequestBuilder<M>, CompletionError> {
+        let dynamic_context = self
+            .fetch_dynamic_context(prompt)
+            .await?
+            .into_iter()
+            .map(|(_, doc)| doc)
+            .collect();
+
+        self.completion_with_context(prompt, chat_history, dynamic_context)
+            .await
+    }
+}
+
 impl<M: CompletionModel> Prompt for Agent<M> {
     async fn prompt(&self, prompt: &str) -> Result<String, PromptError> {
         self.chat(prompt, vec![]).await
@@ -259,16 +368,8 @@ impl<M: CompletionModel> Prompt for &Agent<M> {
 
 impl<M: CompletionModel> Chat for Agent<M> {
     async fn chat(&self, prompt: &str, chat_history: Vec<Message>) -> Result<String, PromptError> {
-        match self.completion(prompt, chat_history).await?.send().await? {
-            CompletionResponse {
-                choice: ModelChoice::Message(msg),
-                ..
-            } => Ok(msg),
-            CompletionResponse {
-                choice: ModelChoice::ToolCall(toolname, args),
-                ..
-            } => Ok(self.tools.call(&toolname, args.to_string()).await?),
-        }
+        self.send_and_resolve(self.completion(prompt, chat_history).await?)
+            .await
     }
 }
 
@@ -314,6 +415,8 @@ pub struct AgentBuilder<M: CompletionModel> {
     temperature: Option<f64>,
     /// Actual tool implementations
     tools: ToolSet,
+    /// Metadata attached to every completion request of the agent
+    metadata: BTreeMap<String, String>,
 }
 
 impl<M: CompletionModel> AgentBuilder<M> {
@@ -329,6 +432,7 @@ impl<M: CompletionModel> AgentBuilder<M> {
             dynamic_context: vec![],
             dynamic_tools: vec![],
             tools: ToolSet::default(),
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -409,6 +513,13 @@ impl<M: CompletionModel> AgentBuilder<M> {
         self
     }
 
+    /// Add a metadata entry (e.g.: tenant or feature name) to every completion request of the agent.
+    /// See [CompletionRequestBuilder::metadata] for more information.
+    pub fn metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
     /// Build the agent
     pub fn build(self) -> Agent<M> {
         Agent {
@@ -422,6 +533,167 @@ impl<M: CompletionModel> AgentBuilder<M> {
             dynamic_context: self.dynamic_context,
             dynamic_tools: self.dynamic_tools,
             tools: self.tools,
+            metadata: self.metadata,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::{AgentBuilder, CitedSource};
+    use crate::{
+        completion::Prompt,
+        completion::{
+            CompletionError, CompletionModel, CompletionRequest, CompletionResponse, ModelChoice,
+        },
+        embeddings::Embedding,
+        test_utils::MockEmbeddingModel,
+        vector_store::{in_memory_store::InMemoryVectorStore, VectorStoreError, VectorStoreIndex},
+        OneOrMany,
+    };
+
+    /// Completion model answering with the prompt it received, including the context documents
+    #[derive(Clone)]
+    struct EchoCompletionModel;
+
+    impl CompletionModel for EchoCompletionModel {
+        type Response = ();
+
+        async fn completion(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse<()>, CompletionError> {
+            Ok(CompletionResponse {
+                choice: ModelChoice::Message(request.prompt_with_context()),
+                logprobs: None,
+                raw_response: (),
+            })
         }
     }
+
+    #[derive(Clone)]
+    struct MockCompletionModel;
+
+    impl CompletionModel for MockCompletionModel {
+        type Response = ();
+
+        async fn completion(
+            &self,
+            request: CompletionRequest,
+        ) -> Result<CompletionResponse<()>, CompletionError> {
+            // Answer with the ids of the documents provided to the model
+            Ok(CompletionResponse {
+                choice: ModelChoice::Message(
+                    request
+                        .documents
+                        .iter()
+                        .map(|doc| doc.id.clone())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                ),
+                logprobs: None,
+                raw_response: (),
+            })
+        }
+    }
+
+    struct MockIndex;
+
+    impl VectorStoreIndex for MockIndex {
+        async fn top_n<T: for<'a> Deserialize<'a> + Send>(
+            &self,
+            _query: &str,
+            n: usize,
+        ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
+            [(0.9, "doc1", "glarb-glarb"), (0.5, "doc2", "linglingdong")]
+                .into_iter()
+                .take(n)
+                .map(|(score, id, doc)| {
+                    Ok((score, id.to_string(), serde_json::from_value(doc.into())?))
+                })
+                .collect()
+        }
+
+        async fn top_n_ids(
+            &self,
+            _query: &str,
+            n: usize,
+        ) -> Result<Vec<(f64, String)>, VectorStoreError> {
+            Ok([(0.9, "doc1"), (0.5, "doc2")]
+                .into_iter()
+                .take(n)
+                .map(|(score, id)| (score, id.to_string()))
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prompt_with_citations() {
+        let agent = AgentBuilder::new(MockCompletionModel)
+            .context("Static definition")
+            .dynamic_context(2, MockIndex)
+            .build();
+
+        let (answer, sources) = agent
+            .prompt_with_citations("What is glarb-glarb?")
+            .await
+            .unwrap();
+
+        assert_eq!(answer, "static_doc_0,doc1,doc2");
+        assert_eq!(
+            sources,
+            vec![
+                CitedSource {
+                    id: "static_doc_0".to_string(),
+                    text: "Static definition".to_string(),
+                    score: None,
+                },
+                CitedSource {
+                    id: "doc1".to_string(),
+                    text: "\"glarb-glarb\"".to_string(),
+                    score: Some(0.9),
+                },
+                CitedSource {
+                    id: "doc2".to_string(),
+                    text: "\"linglingdong\"".to_string(),
+                    score: Some(0.5),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_context_in_memory_store() {
+        let vector_store = InMemoryVectorStore::from_documents_with_ids(vec![
+            (
+                "doc0",
+                "A glarb-glarb is an ancient farming tool".to_string(),
+                OneOrMany::one(Embedding {
+                    document: "glarb-glarb".to_string(),
+                    vec: vec![1.0, 0.0],
+                }),
+            ),
+            (
+                "doc1",
+                "A linglingdong is a term for humans".to_string(),
+                OneOrMany::one(Embedding {
+                    document: "linglingdong".to_string(),
+                    vec: vec![0.0, 1.0],
+                }),
+            ),
+        ]);
+
+        let agent = AgentBuilder::new(EchoCompletionModel)
+            .dynamic_context(1, vector_store.index(MockEmbeddingModel::default()))
+            .build();
+
+        let prompt = agent.prompt("What is a glarb-glarb?").await.unwrap();
+
+        assert!(prompt.contains("<file id: doc0>"));
+        assert!(prompt.contains("A glarb-glarb is an ancient farming tool"));
+        assert!(!prompt.contains("doc1"));
+        assert!(prompt.ends_with("What is a glarb-glarb?"));
+    }
 }
@@ -62,10 +62,11 @@
 //!
 //! For more information on how to use the completion functionality, refer to the documentation of
 //! the individual traits, structs, and enums defined in this module.
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tracing::Instrument;
 
 use crate::{json_utils, tool::ToolSetError};
 
@@ -211,10 +212,29 @@ pub trait Completion<M: CompletionModel> {
 pub struct CompletionResponse<T> {
     /// The completion choice returned by the completion model provider
     pub choice: ModelChoice,
+    /// The log probabilities of the generated tokens, if requested with
+    /// [CompletionRequestBuilder::logprobs] and supported by the provider
+    pub logprobs: Option<Vec<TokenLogProb>>,
     /// The raw response returned by the completion model provider
     pub raw_response: T,
 }
 
+/// Log probability of a token generated by the completion model.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenLogProb {
+    pub token: String,
+    pub logprob: f64,
+    /// Most likely tokens at this position, if requested with [CompletionRequestBuilder::top_logprobs]
+    pub top_logprobs: Vec<TopLogProb>,
+}
+
+/// Log probability of one of the most likely tokens at a position of the completion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TopLogProb {
+    pub token: String,
+    pub logprob: f64,
+}
+
 /// Enum representing the high-level completion choice returned by the completion model provider.
 #[derive(Debug)]
 pub enum ModelChoice {
@@ -261,6 +281,10 @@ pub struct CompletionRequest {
     pub temperature: Option<f64>,
     /// The max tokens to be sent to the completion model provider
     pub max_tokens: Option<u64>,
+    /// Whether to return the log probabilities of the generated tokens
+    pub logprobs: Option<bool>,
+    /// The number of most likely tokens to return at each position, with their log probabilities
+    pub top_logprobs: Option<u8>,
     /// Additional provider-specific parameters to be sent to the completion model provider
     pub additional_params: Option<serde_json::Value>,
 }
@@ -336,7 +360,10 @@ pub struct CompletionRequestBuilder<M: CompletionModel> {
     tools: Vec<ToolDefinition>,
     temperature: Option<f64>,
     max_tokens: Option<u64>,
+    logprobs: Option<bool>,
+    top_logprobs: Option<u8>,
     additional_params: Option<serde_json::Value>,
+    metadata: BTreeMap<String, String>,
 }
 
 impl<M: CompletionModel> CompletionRequestBuilder<M> {
@@ -350,7 +377,10 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             tools: Vec::new(),
             temperature: None,
             max_tokens: None,
+            logprobs: None,
+            top_logprobs: None,
             additional_params: None,
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -452,6 +482,32 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
         self
     }
 
+    /// Sets whether the log probabilities of the generated tokens are returned in
+    /// [CompletionResponse::logprobs].
+    /// Note: This is only supported by some providers (e.g.: OpenAI), and ignored by the others.
+    pub fn logprobs(mut self, logprobs: bool) -> Self {
+        self.logprobs = Some(logprobs);
+        self
+    }
+
+    /// Sets the number of most likely tokens to return at each position, with their log probabilities.
+    /// Note: This requires [CompletionRequestBuilder::logprobs] to be enabled.
+    pub fn top_logprobs(mut self, top_logprobs: u8) -> Self {
+        self.top_logprobs = Some(top_logprobs);
+        self
+    }
+
+    /// Adds a metadata entry (e.g.: tenant or feature name) to the completion request.
+    /// Metadata is not sent to the model provider. It is recorded as the `metadata` field of the
+    /// `completion` tracing span wrapping the request, which allows slicing latency and cost
+    /// in your telemetry.
+    ///
+    /// Note: Metadata is emitted as-is in traces, so it should not contain sensitive information.
+    pub fn metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
     /// Builds the completion request.
     pub fn build(self) -> CompletionRequest {
         CompletionRequest {
@@ -462,6 +518,8 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             tools: self.tools,
             temperature: self.temperature,
             max_tokens: self.max_tokens,
+            logprobs: self.logprobs,
+            top_logprobs: self.top_logprobs,
             additional_params: self.additional_params,
         }
     }
@@ -469,14 +527,83 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
     /// Sends the completion request to the completion model provider and returns the completion response.
     pub async fn send(self) -> Result<CompletionResponse<M::Response>, CompletionError> {
         let model = self.model.clone();
-        model.completion(self.build()).await
+        let span = tracing::info_span!(target: "rig", "completion", metadata = ?self.metadata);
+        model.completion(self.build()).instrument(span).await
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id},
+        Subscriber,
+    };
+    use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
     use super::*;
 
+    #[derive(Clone)]
+    struct MockCompletionModel;
+
+    impl CompletionModel for MockCompletionModel {
+        type Response = ();
+
+        async fn completion(
+            &self,
+            _request: CompletionRequest,
+        ) -> Result<CompletionResponse<()>, CompletionError> {
+            Ok(CompletionResponse {
+                choice: ModelChoice::Message("Hello!".into()),
+                logprobs: None,
+                raw_response: (),
+            })
+        }
+    }
+
+    /// Layer recording the name and fields of every created span
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<Mutex<Vec<String>>>);
+
+    impl<S: Subscriber> Layer<S> for SpanRecorder {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            struct Visitor(String);
+
+            impl Visit for Visitor {
+                fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                    self.0.push_str(&format!(" {}={:?}", field.name(), value));
+                }
+            }
+
+            let mut visitor = Visitor(attrs.metadata().name().to_string());
+            attrs.record(&mut visitor);
+            self.0.lock().unwrap().push(visitor.0);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_in_span() {
+        let recorder = SpanRecorder::default();
+        let _guard = tracing_subscriber::registry()
+            .with(recorder.clone())
+            .set_default();
+
+        MockCompletionModel
+            .completion_request("Hello?")
+            .metadata("tenant", "acme")
+            .metadata("feature", "search")
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![r#"completion metadata={"feature": "search", "tenant": "acme"}"#.to_string()]
+        );
+    }
+
     #[test]
     fn test_document_display_without_metadata() {
         let doc = Document {
@@ -532,6 +659,8 @@ mod tests {
             tools: Vec::new(),
             temperature: None,
             max_tokens: None,
+            logprobs: None,
+            top_logprobs: None,
             additional_params: None,
         };
 
@@ -2,7 +2,7 @@
 //! and batch generates the embeddings for each object when built.
 //! Only types that implement the [Embed] trait can be added to the [EmbeddingsBuilder].
 
-use std::{cmp::max, collections::HashMap};
+use std::{cmp::max, collections::HashMap, time::Duration};
 
 use futures::{stream, StreamExt};
 
@@ -50,6 +50,8 @@ use crate::{
 pub struct EmbeddingsBuilder<M: EmbeddingModel, T: Embed> {
     model: M,
     documents: Vec<(T, Vec<String>)>,
+    max_concurrent: Option<usize>,
+    requests_per_minute: Option<u32>,
 }
 
 impl<M: EmbeddingModel, T: Embed> EmbeddingsBuilder<M, T> {
@@ -58,9 +60,34 @@ impl<M: EmbeddingModel, T: Embed> EmbeddingsBuilder<M, T> {
         Self {
             model,
             documents: vec![],
+            max_concurrent: None,
+            requests_per_minute: None,
         }
     }
 
+    /// Set the maximum number of embedding requests sent concurrently to the model provider.
+    /// By default, up to `1024 / M::MAX_DOCUMENTS` requests are sent concurrently.
+    ///
+    /// Panics if `max_concurrent` is 0.
+    pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+        assert!(max_concurrent > 0, "max_concurrent must be greater than 0");
+        self.max_concurrent = Some(max_concurrent);
+        self
+    }
+
+    /// Pace the embedding requests so that at most `requests_per_minute` requests are started per minute,
+    /// e.g.: to stay below the rate limits of the model provider. Requests are not paced by default.
+    ///
+    /// Panics if `requests_per_minute` is 0.
+    pub fn requests_per_minute(mut self, requests_per_minute: u32) -> Self {
+        assert!(
+            requests_per_minute > 0,
+            "requests_per_minute must be greater than 0"
+        );
+        self.requests_per_minute = Some(requests_per_minute);
+        self
+    }
+
     /// Add a document to be embedded to the builder. `document` must implement the [Embed] trait.
     pub fn document(mut self, document: T) -> Result<Self, EmbedError> {
         let mut embedder = TextEmbedder::default();
@@ -98,21 +125,37 @@ impl<M: EmbeddingModel, T: Embed + Send> EmbeddingsBuilder<M, T> {
             texts.insert(i, doc_texts);
         }
 
+        let max_concurrent = self
+            .max_concurrent
+            .unwrap_or(max(1, 1024 / M::MAX_DOCUMENTS));
+        // Minimum interval between the start of two requests
+        let interval = self
+            .requests_per_minute
+            .map(|rpm| Duration::from_secs(60) / rpm);
+        let start = tokio::time::Instant::now();
+        let model = &self.model;
+
         // Compute the embeddings.
         let mut embeddings = stream::iter(texts.into_iter())
             // Merge the texts of each document into a single list of texts.
             .flat_map(|(i, texts)| stream::iter(texts.into_iter().map(move |text| (i, text))))
             // Chunk them into batches. Each batch size is at most the embedding API limit per request.
             .chunks(M::MAX_DOCUMENTS)
+            .enumerate()
             // Generate the embeddings for each batch.
-            .map(|text| async {
+            .map(|(n, text)| async move {
+                // Wait for the time slot of the request, if requests are paced
+                if let Some(interval) = interval {
+                    tokio::time::sleep_until(start + interval * n as u32).await;
+                }
+
                 let (ids, docs): (Vec<_>, Vec<_>) = text.into_iter().unzip();
 
-                let embeddings = self.model.embed_texts(docs).await?;
+                let embeddings = model.embed_texts(docs).await?;
                 Ok::<_, EmbeddingError>(ids.into_iter().zip(embeddings).collect::<Vec<_>>())
             })
-            // Parallelize the embeddings generation over 10 concurrent requests
-            .buffer_unordered(max(1, 1024 / M::MAX_DOCUMENTS))
+            // Parallelize the embeddings generation over at most `max_concurrent` concurrent requests
+            .buffer_unordered(max_concurrent)
             // Collect the embeddings into a HashMap.
             .try_fold(
                 HashMap::new(),
@@ -148,6 +191,14 @@ mod tests {
         Embed,
     };
 
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc, Mutex,
+        },
+        time::{Duration, Instant},
+    };
+
     use super::EmbeddingsBuilder;
 
     #[derive(Clone)]
@@ -352,6 +403,92 @@ mod tests {
         )
     }
 
+    /// Model recording the maximum number of concurrent requests and the start time of each request.
+    #[derive(Clone, Default)]
+    struct SlowModel {
+        in_flight: Arc<AtomicUsize>,
+        max_in_flight: Arc<AtomicUsize>,
+        starts: Arc<Mutex<Vec<Instant>>>,
+    }
+
+    impl EmbeddingModel for SlowModel {
+        const MAX_DOCUMENTS: usize = 1;
+
+        fn ndims(&self) -> usize {
+            1
+        }
+
+        async fn embed_texts(
+            &self,
+            documents: impl IntoIterator<Item = String> + Send,
+        ) -> Result<Vec<crate::embeddings::Embedding>, crate::embeddings::EmbeddingError> {
+            self.starts.lock().unwrap().push(Instant::now());
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(in_flight, Ordering::SeqCst);
+
+            tokio::time::sleep(Duration::from_millis(10)).await;
+
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(documents
+                .into_iter()
+                .map(|doc| Embedding {
+                    document: doc,
+                    vec: vec![0.0],
+                })
+                .collect())
+        }
+    }
+
+    fn words(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("word{i}")).collect()
+    }
+
+    #[tokio::test]
+    async fn test_build_max_concurrent() {
+        let model = SlowModel::default();
+        let result = EmbeddingsBuilder::new(model.clone())
+            .documents(words(8))
+            .unwrap()
+            .max_concurrent(2)
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 8);
+        assert_eq!(model.max_in_flight.load(Ordering::SeqCst), 2);
+
+        // Default concurrency is unchanged
+        let model = SlowModel::default();
+        EmbeddingsBuilder::new(model.clone())
+            .documents(words(8))
+            .unwrap()
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(model.max_in_flight.load(Ordering::SeqCst), 8);
+    }
+
+    #[tokio::test]
+    async fn test_build_requests_per_minute() {
+        let model = SlowModel::default();
+        let result = EmbeddingsBuilder::new(model.clone())
+            .documents(words(4))
+            .unwrap()
+            // One request every 20ms
+            .requests_per_minute(3000)
+            .build()
+            .await
+            .unwrap();
+
+        assert_eq!(result.len(), 4);
+
+        let mut starts = model.starts.lock().unwrap().clone();
+        starts.sort();
+        assert!(starts
+            .windows(2)
+            .all(|w| w[1] - w[0] >= Duration::from_millis(15)));
+    }
+
     #[tokio::test]
     async fn test_build_string() {
         let bindings = definitions_multiple_text();
@@ -1,3 +1,6 @@
+#[cfg(not(feature = "rayon"))]
+use crate::vector_store::math::fused_cosine_similarity;
+
 pub trait VectorDistance {
     /// Get dot product of two embedding vectors
     fn dot_product(&self, other: &Self) -> f64;
@@ -30,15 +33,10 @@ impl VectorDistance for crate::embeddings::Embedding {
     }
 
     fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
-        let dot_product = self.dot_product(other);
-
         if normalized {
-            dot_product
+            self.dot_product(other)
         } else {
-            let magnitude1: f64 = self.vec.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
-            let magnitude2: f64 = other.vec.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
-
-            dot_product / (magnitude1 * magnitude2)
+            fused_cosine_similarity(&self.vec, &other.vec)
         }
     }
 
@@ -75,7 +73,10 @@ impl VectorDistance for crate::embeddings::Embedding {
 
 #[cfg(feature = "rayon")]
 mod rayon {
-    use crate::embeddings::{distance::VectorDistance, Embedding};
+    use crate::{
+        embeddings::{distance::VectorDistance, Embedding},
+        vector_store::math::fused_cosine_similarity,
+    };
     use rayon::prelude::*;
 
     impl VectorDistance for Embedding {
@@ -88,15 +89,10 @@ mod rayon {
         }
 
         fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
-            let dot_product = self.dot_product(other);
-
             if normalized {
-                dot_product
+                self.dot_product(other)
             } else {
-                let magnitude1: f64 = self.vec.par_iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
-                let magnitude2: f64 = other.vec.par_iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
-
-                dot_product / (magnitude1 * magnitude2)
+                fused_cosine_similarity(&self.vec, &other.vec)
             }
         }
 
@@ -168,6 +164,18 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_cosine_similarity_zero_vector() {
+        let (embedding_1, _) = embeddings();
+        let zero = Embedding {
+            document: "zero".to_string(),
+            vec: vec![0.0, 0.0, 0.0],
+        };
+
+        // Same result with and without the `rayon` feature
+        assert_eq!(embedding_1.cosine_similarity(&zero, false), 0.0)
+    }
+
     #[test]
     fn test_angular_distance() {
         let (embedding_1, embedding_2) = embeddings();
@@ -180,6 +180,16 @@ impl<T: Embed> Embed for &T {
     }
 }
 
+/// `None` values contribute no text to embed.
+impl<T: Embed> Embed for Option<T> {
+    fn embed(&self, embedder: &mut TextEmbedder) -> Result<(), EmbedError> {
+        match self {
+            Some(item) => item.embed(embedder),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<T: Embed> Embed for Vec<T> {
     fn embed(&self, embedder: &mut TextEmbedder) -> Result<(), EmbedError> {
         for item in self {
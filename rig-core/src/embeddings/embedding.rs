@@ -8,6 +8,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::vector_store::math::fused_cosine_similarity;
+
 #[derive(Debug, thiserror::Error)]
 pub enum EmbeddingError {
     /// Http error (e.g.: connection error, timeout, etc.)
@@ -29,6 +31,10 @@ pub enum EmbeddingError {
     /// Error returned by the embedding model provider
     #[error("ProviderError: {0}")]
     ProviderError(String),
+
+    /// Two embeddings with a different number of dimensions were compared
+    #[error("DimensionMismatch: expected {0} dimensions, got {1}")]
+    DimensionMismatch(usize, usize),
 }
 
 /// Trait for embedding models that can generate embeddings for documents.
@@ -60,6 +66,19 @@ pub trait EmbeddingModel: Clone + Sync + Send {
     }
 }
 
+/// Trait for embedding models that can also embed images, in the same vector space as texts
+/// (e.g.: to search product images with a text query).
+///
+/// It is separate from [EmbeddingModel] so that text-only models don't have to implement it.
+pub trait MultimodalEmbeddingModel: EmbeddingModel {
+    /// Embed a single image given its raw bytes and MIME type (e.g.: `image/png`).
+    fn embed_image(
+        &self,
+        bytes: &[u8],
+        mime: &str,
+    ) -> impl std::future::Future<Output = Result<Embedding, EmbeddingError>> + Send;
+}
+
 /// Struct that holds a single document and its embedding.
 #[derive(Clone, Default, Deserialize, Serialize, Debug)]
 pub struct Embedding {
@@ -76,3 +95,99 @@ impl PartialEq for Embedding {
 }
 
 impl Eq for Embedding {}
+
+impl Embedding {
+    /// Dot product of the two embedding vectors.
+    /// Returns an error if the embeddings don't have the same number of dimensions.
+    pub fn checked_dot(&self, other: &Embedding) -> Result<f64, EmbeddingError> {
+        self.check_ndims(other)?;
+        Ok(self
+            .vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| x * y)
+            .sum())
+    }
+
+    /// Cosine similarity of the two embedding vectors, `0.0` if either vector is zero.
+    /// Returns an error if the embeddings don't have the same number of dimensions.
+    pub fn checked_cosine_similarity(&self, other: &Embedding) -> Result<f64, EmbeddingError> {
+        self.check_ndims(other)?;
+        Ok(fused_cosine_similarity(&self.vec, &other.vec))
+    }
+
+    /// Euclidean distance between the two embedding vectors.
+    /// Returns an error if the embeddings don't have the same number of dimensions.
+    pub fn checked_euclidean_distance(&self, other: &Embedding) -> Result<f64, EmbeddingError> {
+        self.check_ndims(other)?;
+        Ok(self
+            .vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (x - y).powi(2))
+            .sum::<f64>()
+            .sqrt())
+    }
+
+    fn check_ndims(&self, other: &Embedding) -> Result<(), EmbeddingError> {
+        if self.vec.len() != other.vec.len() {
+            return Err(EmbeddingError::DimensionMismatch(
+                self.vec.len(),
+                other.vec.len(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Embedding, EmbeddingError};
+
+    fn embedding(vec: Vec<f64>) -> Embedding {
+        Embedding {
+            document: "test".to_string(),
+            vec,
+        }
+    }
+
+    #[test]
+    fn test_embedding_helpers() {
+        let embedding_1 = embedding(vec![1.0, 2.0, 3.0]);
+        let embedding_2 = embedding(vec![1.0, 5.0, 7.0]);
+
+        assert_eq!(embedding_1.checked_dot(&embedding_2).unwrap(), 32.0);
+        assert!(
+            (embedding_1.checked_cosine_similarity(&embedding_2).unwrap() - 0.9875414397573881)
+                .abs()
+                < 1e-12
+        );
+        assert_eq!(
+            embedding_1
+                .checked_euclidean_distance(&embedding_2)
+                .unwrap(),
+            5.0
+        );
+        assert_eq!(
+            embedding_1
+                .checked_cosine_similarity(&embedding(vec![0.0, 0.0, 0.0]))
+                .unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_embedding_helpers_dimension_mismatch() {
+        let embedding_1 = embedding(vec![1.0, 2.0, 3.0]);
+        let embedding_2 = embedding(vec![1.0, 2.0]);
+
+        assert!(matches!(
+            embedding_1.checked_dot(&embedding_2),
+            Err(EmbeddingError::DimensionMismatch(3, 2))
+        ));
+        assert!(embedding_1.checked_cosine_similarity(&embedding_2).is_err());
+        assert!(embedding_1
+            .checked_euclidean_distance(&embedding_2)
+            .is_err());
+    }
+}
@@ -11,5 +11,5 @@ pub mod tool;
 pub mod distance;
 pub use builder::EmbeddingsBuilder;
 pub use embed::{to_texts, Embed, EmbedError, TextEmbedder};
-pub use embedding::{Embedding, EmbeddingError, EmbeddingModel};
+pub use embedding::{Embedding, EmbeddingError, EmbeddingModel, MultimodalEmbeddingModel};
 pub use tool::ToolSchema;
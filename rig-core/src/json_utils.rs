@@ -17,3 +17,36 @@ pub fn merge_inplace(a: &mut serde_json::Value, b: serde_json::Value) {
         });
     }
 }
+
+/// Read the body of a provider response as text, e.g.: before deserializing it.
+///
+/// The raw body is logged at `trace` level, to debug responses that do not deserialize
+/// or that behave unexpectedly (e.g.: with `RUST_LOG=rig=trace`).
+pub(crate) async fn response_text(response: reqwest::Response) -> Result<String, reqwest::Error> {
+    let url = log_url(response.url());
+    let body = response.text().await?;
+    tracing::trace!(target: "rig", "Raw response from {}: {}", url, body);
+    Ok(body)
+}
+
+/// URL of a request without its query string, which can contain credentials
+/// (e.g.: Gemini's `?key=<API key>`).
+fn log_url(url: &reqwest::Url) -> String {
+    format!("{}{}", url.origin().ascii_serialization(), url.path())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_log_url_strips_query() {
+        let url = reqwest::Url::parse(
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-pro:generateContent?key=secret",
+        )
+        .unwrap();
+
+        assert_eq!(
+            super::log_url(&url),
+            "https://generativelanguage.googleapis.com/v1beta/models/gemini-1.5-pro:generateContent"
+        );
+    }
+}
@@ -88,6 +88,8 @@ pub mod loaders;
 pub mod one_or_many;
 pub mod pipeline;
 pub mod providers;
+#[cfg(test)]
+pub(crate) mod test_utils;
 pub mod tool;
 pub mod vector_store;
 
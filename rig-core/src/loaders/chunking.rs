@@ -0,0 +1,258 @@
+//! Utilities to split long documents into overlapping chunks, e.g.: before embedding them.
+//!
+//! Chunks are made of whole words whenever possible, and end at the end of a sentence or
+//! paragraph if there is one in the second half of the chunk. Words longer than a whole chunk
+//! are split between characters.
+//!
+//! # Example
+//! ```rust
+//! use rig::loaders::chunking::{chunk_by_chars, chunk_by_tokens};
+//!
+//! let text = "First sentence. Second sentence, a bit longer. Third one.";
+//!
+//! // Chunks of at most 30 characters, overlapping by at most 10 characters
+//! let chunks = chunk_by_chars(text, 30, 10);
+//!
+//! // Chunks of at most 8 tokens, using a naive whitespace tokenizer
+//! let chunks = chunk_by_tokens(text, 8, 2, &|text: &str| text.split_whitespace().count());
+//! ```
+use std::ops::Range;
+
+/// A chunk of a document. `start` and `end` are the byte offsets of the chunk in the document,
+/// i.e.: `&document[chunk.start..chunk.end] == chunk.text`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    pub text: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Counts the tokens of a text, e.g.: using the tokenizer of an embedding model.
+pub trait Tokenizer {
+    fn count_tokens(&self, text: &str) -> usize;
+}
+
+impl<F: Fn(&str) -> usize> Tokenizer for F {
+    fn count_tokens(&self, text: &str) -> usize {
+        self(text)
+    }
+}
+
+/// Split `text` into chunks of at most `size` characters, where consecutive chunks share up to
+/// `overlap` characters.
+///
+/// Panics if `size` is 0 or if `overlap` is not smaller than `size`.
+pub fn chunk_by_chars(text: &str, size: usize, overlap: usize) -> Vec<Chunk> {
+    chunk(text, size, overlap, |text| text.chars().count())
+}
+
+/// Split `text` into chunks of at most `size` tokens (as counted by `tokenizer`), where
+/// consecutive chunks share up to `overlap` tokens.
+///
+/// Panics if `size` is 0 or if `overlap` is not smaller than `size`.
+pub fn chunk_by_tokens(
+    text: &str,
+    size: usize,
+    overlap: usize,
+    tokenizer: &impl Tokenizer,
+) -> Vec<Chunk> {
+    chunk(text, size, overlap, |text| tokenizer.count_tokens(text))
+}
+
+fn chunk(text: &str, size: usize, overlap: usize, measure: impl Fn(&str) -> usize) -> Vec<Chunk> {
+    assert!(size > 0, "Chunk size must be greater than 0");
+    assert!(
+        overlap < size,
+        "Chunk overlap must be smaller than the chunk size"
+    );
+
+    let units = units(text, size, &measure);
+    // Text of units `i..=j`, without trailing whitespace
+    let span = |i: usize, j: usize| text[units[i].start..units[j].end].trim_end();
+
+    let mut chunks = vec![];
+    let mut i = 0;
+
+    while i < units.len() {
+        // Extend the chunk with as many units as fit (always at least one)
+        let mut j = i;
+        while j + 1 < units.len() && measure(span(i, j + 1)) <= size {
+            j += 1;
+        }
+
+        // Prefer ending the chunk at a sentence or paragraph boundary in its second half
+        if j + 1 < units.len() {
+            let half = units[i].start + (units[j].end - units[i].start) / 2;
+            if let Some(boundary) = (i..=j)
+                .rev()
+                .take_while(|&k| units[k].end > half)
+                .find(|&k| is_boundary(&text[units[k].clone()]))
+            {
+                j = boundary;
+            }
+        }
+
+        let chunk_text = span(i, j);
+        chunks.push(Chunk {
+            text: chunk_text.to_string(),
+            start: units[i].start,
+            end: units[i].start + chunk_text.len(),
+        });
+
+        if j + 1 == units.len() {
+            break;
+        }
+
+        // Start the next chunk with the last units of this chunk that fit in the overlap,
+        // while always moving forward
+        let mut next = j + 1;
+        while next > i + 1 && overlap > 0 && measure(span(next - 1, j)) <= overlap {
+            next -= 1;
+        }
+        i = next;
+    }
+
+    chunks
+}
+
+/// Split `text` into units made of a word and its trailing whitespace. Words that are longer
+/// than `size` are split between characters.
+fn units(text: &str, size: usize, measure: &impl Fn(&str) -> usize) -> Vec<Range<usize>> {
+    let mut units = vec![];
+    // Leading whitespace is not part of any unit
+    let first = text.len() - text.trim_start().len();
+    let mut start = first;
+    let mut in_whitespace = false;
+
+    for (idx, c) in text.char_indices().skip_while(|(idx, _)| *idx < first) {
+        if c.is_whitespace() {
+            in_whitespace = true;
+        } else if in_whitespace {
+            units.push(start..idx);
+            start = idx;
+            in_whitespace = false;
+        }
+    }
+    if start < text.len() {
+        units.push(start..text.len());
+    }
+
+    units
+        .into_iter()
+        .flat_map(|unit| {
+            if measure(text[unit.clone()].trim_end()) <= size {
+                return vec![unit];
+            }
+
+            // Greedily split the word between characters
+            let mut pieces = vec![];
+            let mut start = unit.start;
+            let mut end = start;
+            for (idx, c) in text[unit.clone()].trim_end().char_indices() {
+                let idx = unit.start + idx;
+                if idx > start && measure(&text[start..idx + c.len_utf8()]) > size {
+                    pieces.push(start..end);
+                    start = idx;
+                }
+                end = idx + c.len_utf8();
+            }
+            pieces.push(start..unit.end);
+            pieces
+        })
+        .collect()
+}
+
+/// Whether a unit ends a sentence or a paragraph.
+fn is_boundary(unit: &str) -> bool {
+    let word = unit.trim_end();
+    word.ends_with(['.', '!', '?']) || unit[word.len()..].matches('\n').count() >= 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{chunk_by_chars, chunk_by_tokens, Chunk};
+
+    fn texts(chunks: &[Chunk]) -> Vec<&str> {
+        chunks.iter().map(|chunk| chunk.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_chunk_by_chars() {
+        let text = "The quick brown fox jumps over the lazy dog";
+        let chunks = chunk_by_chars(text, 15, 0);
+
+        assert_eq!(
+            texts(&chunks),
+            vec!["The quick brown", "fox jumps over", "the lazy dog"]
+        );
+        for chunk in &chunks {
+            assert!(chunk.text.chars().count() <= 15);
+            assert_eq!(&text[chunk.start..chunk.end], chunk.text);
+        }
+    }
+
+    #[test]
+    fn test_chunk_by_chars_overlap() {
+        let text = "one two three four five six";
+        let chunks = chunk_by_chars(text, 13, 8);
+
+        assert_eq!(
+            texts(&chunks),
+            vec!["one two three", "three four", "four five six"]
+        );
+    }
+
+    #[test]
+    fn test_chunk_boundaries() {
+        let text = "Flurbos are green. They live on Jiro.";
+        let chunks = chunk_by_chars(text, 30, 0);
+
+        assert_eq!(
+            texts(&chunks),
+            vec!["Flurbos are green.", "They live on Jiro."]
+        );
+
+        let text = "Flurbos are green and live on Jiro\n\nGlarbs are not";
+        let chunks = chunk_by_chars(text, 45, 0);
+
+        assert_eq!(
+            texts(&chunks),
+            vec!["Flurbos are green and live on Jiro", "Glarbs are not"]
+        );
+    }
+
+    #[test]
+    fn test_chunk_long_word() {
+        let chunks = chunk_by_chars("a linglingdong b", 5, 0);
+
+        assert_eq!(texts(&chunks), vec!["a", "lingl", "ingdo", "ng b"]);
+    }
+
+    #[test]
+    fn test_chunk_empty() {
+        assert!(chunk_by_chars("", 10, 0).is_empty());
+        assert!(chunk_by_chars("   ", 10, 0).is_empty());
+
+        let chunks = chunk_by_chars("  flurbo  ", 10, 0);
+        assert_eq!(
+            chunks,
+            vec![Chunk {
+                text: "flurbo".to_string(),
+                start: 2,
+                end: 8
+            }]
+        );
+    }
+
+    #[test]
+    fn test_chunk_by_tokens() {
+        let tokenizer = |text: &str| text.split_whitespace().count();
+        let text = "one two three four five six seven";
+        let chunks = chunk_by_tokens(text, 3, 1, &tokenizer);
+
+        assert_eq!(
+            texts(&chunks),
+            vec!["one two three", "three four five", "five six seven"]
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use glob::{MatchOptions, Pattern};
+
+use super::file::{FileLoader, FileLoaderError};
+
+/// Number of bytes inspected at the start of a file to detect binary files.
+const BINARY_DETECTION_BYTES: usize = 8192;
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+// ================================================================
+// DirectoryLoader definitions and implementations
+// ================================================================
+
+/// [DirectoryLoader] recursively walks a directory and loads the files matching a set of glob
+///  patterns. The patterns are matched against the path of each file relative to the root
+///  directory (e.g.: `**/*.md` or `docs/*.txt`), and `*` does not match path separators.
+///
+/// The directory tree is walked lazily, and the loader produces a [FileLoader], so the usual
+///  [FileLoader] methods (e.g.: [FileLoader::ignore_errors]) can be used on its output.
+///  Symbolic links to directories are not followed, since they may point back up the tree.
+///
+/// # Example Usage
+///
+/// ```no_run
+/// use rig::loaders::DirectoryLoader;
+///
+/// fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     // Load the markdown files of the repository, except the ones in `target`
+///     let documents = DirectoryLoader::new("path/to/repo")
+///         .include("**/*.md")
+///         .exclude("target")
+///         .max_depth(5)
+///         .read_with_path()?
+///         .ignore_errors()
+///         .into_iter()
+///         .map(|(_path, content)| content)
+///         .collect::<Vec<_>>();
+///
+///     // `documents` can then be added to an `EmbeddingsBuilder`
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirectoryLoader {
+    root: PathBuf,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    max_depth: Option<usize>,
+}
+
+impl DirectoryLoader {
+    /// Creates a new [DirectoryLoader] loading all files under `root`, at any depth.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            include: vec![],
+            exclude: vec![],
+            max_depth: None,
+        }
+    }
+
+    /// Only load files matching the glob `pattern`. If called multiple times, files matching
+    ///  any of the patterns are loaded. By default, all files are loaded.
+    pub fn include(mut self, pattern: &str) -> Self {
+        self.include.push(pattern.to_string());
+        self
+    }
+
+    /// Skip files and directories matching the glob `pattern`. Exclusions take precedence over
+    ///  inclusions, and excluded directories are not walked.
+    pub fn exclude(mut self, pattern: &str) -> Self {
+        self.exclude.push(pattern.to_string());
+        self
+    }
+
+    /// Maximum depth of the subdirectories to walk. `0` only loads the files directly in the root
+    ///  directory. By default, there is no limit.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Returns a [FileLoader] over the paths of the matching files.
+    /// Fails if one of the patterns is invalid or if the root directory cannot be read.
+    pub fn paths(
+        self,
+    ) -> Result<FileLoader<'static, Result<PathBuf, FileLoaderError>>, FileLoaderError> {
+        let walk = Walk {
+            include: compile(&self.include)?,
+            exclude: compile(&self.exclude)?,
+            max_depth: self.max_depth,
+            stack: vec![(fs::read_dir(&self.root)?, 0)],
+            root: self.root,
+        };
+
+        Ok(FileLoader {
+            iterator: Box::new(walk),
+        })
+    }
+
+    /// Returns a [FileLoader] over the paths and contents of the matching files.
+    ///
+    /// Binary files (i.e.: files containing a NUL byte in their first few kilobytes) are skipped
+    ///  with a warning, and invalid UTF-8 sequences in text files are replaced with `U+FFFD`.
+    #[allow(clippy::type_complexity)]
+    pub fn read_with_path(
+        self,
+    ) -> Result<FileLoader<'static, Result<(PathBuf, String), FileLoaderError>>, FileLoaderError>
+    {
+        let paths = self.paths()?;
+
+        Ok(FileLoader {
+            iterator: Box::new(paths.into_iter().filter_map(|path| {
+                match path {
+                    Ok(path) => read_text(&path)
+                        .map(|content| content.map(|content| (path, content)))
+                        .transpose(),
+                    Err(e) => Some(Err(e)),
+                }
+            })),
+        })
+    }
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Pattern>, FileLoaderError> {
+    patterns
+        .iter()
+        .map(|pattern| Pattern::new(pattern).map_err(FileLoaderError::PatternError))
+        .collect()
+}
+
+/// Reads a file as (lossy) UTF-8 text. Returns `None` if the file looks like a binary file.
+fn read_text(path: &Path) -> Result<Option<String>, FileLoaderError> {
+    let bytes = fs::read(path)?;
+
+    if bytes
+        .iter()
+        .take(BINARY_DETECTION_BYTES)
+        .any(|&byte| byte == 0)
+    {
+        tracing::warn!(target: "rig", "Skipping binary file {}", path.display());
+        return Ok(None);
+    }
+
+    Ok(Some(String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+/// Depth-first iterator over the files of a directory tree.
+struct Walk {
+    root: PathBuf,
+    include: Vec<Pattern>,
+    exclude: Vec<Pattern>,
+    max_depth: Option<usize>,
+    /// Directories being walked, with their depth relative to the root
+    stack: Vec<(fs::ReadDir, usize)>,
+}
+
+impl Walk {
+    fn is_excluded(&self, relative: &Path) -> bool {
+        self.exclude
+            .iter()
+            .any(|pattern| pattern.matches_path_with(relative, MATCH_OPTIONS))
+    }
+
+    fn is_included(&self, relative: &Path) -> bool {
+        self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches_path_with(relative, MATCH_OPTIONS))
+    }
+}
+
+impl Iterator for Walk {
+    type Item = Result<PathBuf, FileLoaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (entries, depth) = self.stack.last_mut()?;
+            let depth = *depth;
+
+            let Some(entry) = entries.next() else {
+                self.stack.pop();
+                continue;
+            };
+
+            // Unlike `Path::is_dir`, the file type of an entry does not follow symbolic links
+            let (path, is_symlink) = match entry.and_then(|entry| {
+                let file_type = entry.file_type()?;
+                Ok((entry.path(), file_type.is_symlink()))
+            }) {
+                Ok(entry) => entry,
+                Err(e) => return Some(Err(FileLoaderError::IoError(e))),
+            };
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf();
+
+            if self.is_excluded(&relative) {
+                continue;
+            }
+
+            if path.is_dir() {
+                // Symlinked directories are skipped, as they may point back up the tree
+                if is_symlink {
+                    continue;
+                }
+                if self.max_depth.is_none_or(|max_depth| depth < max_depth) {
+                    match fs::read_dir(&path) {
+                        Ok(entries) => self.stack.push((entries, depth + 1)),
+                        Err(e) => return Some(Err(FileLoaderError::IoError(e))),
+                    }
+                }
+            } else if path.is_file() && self.is_included(&relative) {
+                return Some(Ok(path));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use assert_fs::prelude::{FileWriteBin, FileWriteStr, PathChild};
+
+    use super::DirectoryLoader;
+
+    fn relative_paths(temp: &assert_fs::TempDir, loader: DirectoryLoader) -> Vec<String> {
+        let mut paths = loader
+            .paths()
+            .unwrap()
+            .ignore_errors()
+            .into_iter()
+            .map(|path| {
+                path.strip_prefix(temp.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_directory_loader_filters() {
+        let temp = assert_fs::TempDir::new().expect("Failed to create temp dir");
+        temp.child("README.md").write_str("readme").unwrap();
+        temp.child("notes.txt").write_str("notes").unwrap();
+        temp.child("docs/guide.md").write_str("guide").unwrap();
+        temp.child("docs/api/index.md").write_str("index").unwrap();
+        temp.child("target/out.md").write_str("out").unwrap();
+
+        let root = temp.path().to_path_buf();
+
+        assert_eq!(
+            relative_paths(&temp, DirectoryLoader::new(&root)),
+            vec![
+                "README.md",
+                "docs/api/index.md",
+                "docs/guide.md",
+                "notes.txt",
+                "target/out.md"
+            ]
+        );
+
+        assert_eq!(
+            relative_paths(
+                &temp,
+                DirectoryLoader::new(&root)
+                    .include("**/*.md")
+                    .exclude("target")
+            ),
+            vec!["README.md", "docs/api/index.md", "docs/guide.md"]
+        );
+
+        assert_eq!(
+            relative_paths(
+                &temp,
+                DirectoryLoader::new(&root).include("**/*.md").max_depth(1)
+            ),
+            vec!["README.md", "docs/guide.md", "target/out.md"]
+        );
+
+        assert_eq!(
+            relative_paths(&temp, DirectoryLoader::new(&root).include("*.md")),
+            vec!["README.md"]
+        );
+
+        assert_eq!(
+            relative_paths(&temp, DirectoryLoader::new(&root).max_depth(0)),
+            vec!["README.md", "notes.txt"]
+        );
+
+        assert!(DirectoryLoader::new(&root).include("[").paths().is_err());
+    }
+
+    #[test]
+    fn test_directory_loader_read() {
+        let temp = assert_fs::TempDir::new().expect("Failed to create temp dir");
+        temp.child("text.txt").write_str("hello").unwrap();
+        temp.child("latin1.txt").write_binary(b"caf\xe9").unwrap();
+        temp.child("image.png")
+            .write_binary(b"\x89PNG\0\0")
+            .unwrap();
+
+        let mut contents = DirectoryLoader::new(temp.path())
+            .read_with_path()
+            .unwrap()
+            .ignore_errors()
+            .into_iter()
+            .map(|(path, content)| (path.file_name().map(PathBuf::from).unwrap(), content))
+            .collect::<Vec<_>>();
+        contents.sort();
+
+        assert_eq!(
+            contents,
+            vec![
+                (PathBuf::from("latin1.txt"), "caf\u{FFFD}".to_string()),
+                (PathBuf::from("text.txt"), "hello".to_string()),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_directory_loader_symlink_loop() {
+        let temp = assert_fs::TempDir::new().expect("Failed to create temp dir");
+        temp.child("README.md").write_str("readme").unwrap();
+        temp.child("docs/guide.md").write_str("guide").unwrap();
+        // Symlink pointing back up the tree
+        std::os::unix::fs::symlink(temp.path(), temp.child("docs/root").path()).unwrap();
+        // Symlinked files are still loaded
+        std::os::unix::fs::symlink(
+            temp.child("README.md").path(),
+            temp.child("docs/readme.md").path(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            relative_paths(&temp, DirectoryLoader::new(temp.path())),
+            vec!["README.md", "docs/guide.md", "docs/readme.md"]
+        );
+    }
+
+    #[test]
+    fn test_directory_loader_missing_root() {
+        assert!(DirectoryLoader::new("does/not/exist").paths().is_err());
+    }
+}
@@ -95,7 +95,7 @@ impl<T: Readable> Readable for Result<T, FileLoaderError> {
 /// [FileLoader] uses strict typing between the iterator methods to ensure that transitions between
 ///   different implementations of the loaders and it's methods are handled properly by the compiler.
 pub struct FileLoader<'a, T> {
-    iterator: Box<dyn Iterator<Item = T> + 'a>,
+    pub(crate) iterator: Box<dyn Iterator<Item = T> + 'a>,
 }
 
 impl<'a> FileLoader<'a, Result<PathBuf, FileLoaderError>> {
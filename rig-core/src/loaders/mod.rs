@@ -8,10 +8,18 @@
 //! files. This loader also provides PDF-specific preprocessing methods for splitting the PDF into pages
 //! and keeping track of the page numbers along with their contents.
 //!
+//! The [DirectoryLoader] recursively walks a directory and loads the files matching a set of glob
+//! patterns, skipping binary files.
+//!
+//! The [chunking] module provides helpers to split the loaded documents into overlapping chunks.
+//!
 //! Note: The [PdfFileLoader] requires the `pdf` feature to be enabled in the `Cargo.toml` file.
 
+pub mod chunking;
+pub mod directory;
 pub mod file;
 
+pub use directory::DirectoryLoader;
 pub use file::FileLoader;
 
 #[cfg(feature = "pdf")]
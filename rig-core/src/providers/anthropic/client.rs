@@ -1,6 +1,10 @@
 //! Anthropic client api implementation
 
-use crate::{agent::AgentBuilder, extractor::ExtractorBuilder};
+use crate::{
+    agent::AgentBuilder,
+    extractor::ExtractorBuilder,
+    providers::http_client::{HttpClient, ProviderClient},
+};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -74,8 +78,13 @@ impl<'a> ClientBuilder<'a> {
 
 #[derive(Clone)]
 pub struct Client {
-    base_url: String,
-    http_client: reqwest::Client,
+    pub(crate) http: HttpClient,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
 }
 
 impl Client {
@@ -88,9 +97,9 @@ impl Client {
     /// - If the reqwest client cannot be built (if the TLS backend cannot be initialized).
     pub fn new(api_key: &str, base_url: &str, betas: Option<Vec<&str>>, version: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
-            http_client: reqwest::Client::builder()
-                .default_headers({
+            http: HttpClient::new(
+                base_url,
+                {
                     let mut headers = reqwest::header::HeaderMap::new();
                     headers.insert("x-api-key", api_key.parse().expect("API key should parse"));
                     headers.insert(
@@ -107,9 +116,9 @@ impl Client {
                         );
                     }
                     headers
-                })
-                .build()
-                .expect("Anthropic reqwest client should build"),
+                },
+                &[reqwest::header::HeaderName::from_static("x-api-key")],
+            ),
         }
     }
 
@@ -121,8 +130,7 @@ impl Client {
     }
 
     pub fn post(&self, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}", self.base_url, path).replace("//", "/");
-        self.http_client.post(url)
+        self.http.post(path)
     }
 
     pub fn completion_model(&self, model: &str) -> CompletionModel {
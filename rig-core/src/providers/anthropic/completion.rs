@@ -5,6 +5,7 @@ use std::iter;
 use crate::{
     completion::{self, CompletionError},
     json_utils,
+    providers::{http_client::JsonBody, retry::SendWithRetry},
 };
 
 use serde::{Deserialize, Serialize};
@@ -109,11 +110,13 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             [Content::String(text) | Content::Text { text, .. }, ..] => {
                 Ok(completion::CompletionResponse {
                     choice: completion::ModelChoice::Message(text.to_string()),
+                    logprobs: None,
                     raw_response: response,
                 })
             }
             [Content::ToolUse { name, input, .. }, ..] => Ok(completion::CompletionResponse {
                 choice: completion::ModelChoice::ToolCall(name.clone(), input.clone()),
+                logprobs: None,
                 raw_response: response,
             }),
             _ => Err(CompletionError::ResponseError(
@@ -254,12 +257,13 @@ impl completion::CompletionModel for CompletionModel {
         let response = self
             .client
             .post("/v1/messages")
-            .json(&request)
-            .send()
+            .json_body(&request, self.client.http.gzip_threshold)?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<CompletionResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<CompletionResponse>>(&body)? {
                 ApiResponse::Message(completion) => {
                     tracing::info!(target: "rig",
                         "Anthropic completion token usage: {}",
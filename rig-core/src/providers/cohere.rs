@@ -15,9 +15,15 @@ use crate::{
     completion::{self, CompletionError},
     embeddings::{self, EmbeddingError, EmbeddingsBuilder},
     extractor::ExtractorBuilder,
-    json_utils, Embed,
+    json_utils,
+    providers::{
+        http_client::{HttpClient, JsonBody, ProviderClient},
+        retry::SendWithRetry,
+    },
+    Embed,
 };
 
+use base64::prelude::{Engine, BASE64_STANDARD};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -29,8 +35,13 @@ const COHERE_API_BASE_URL: &str = "https://api.cohere.ai";
 
 #[derive(Clone)]
 pub struct Client {
-    base_url: String,
-    http_client: reqwest::Client,
+    pub(crate) http: HttpClient,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
 }
 
 impl Client {
@@ -40,9 +51,9 @@ impl Client {
 
     pub fn from_url(api_key: &str, base_url: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
-            http_client: reqwest::Client::builder()
-                .default_headers({
+            http: HttpClient::new(
+                base_url,
+                {
                     let mut headers = reqwest::header::HeaderMap::new();
                     headers.insert(
                         "Authorization",
@@ -51,9 +62,9 @@ impl Client {
                             .expect("Bearer token should parse"),
                     );
                     headers
-                })
-                .build()
-                .expect("Cohere reqwest client should build"),
+                },
+                &[reqwest::header::AUTHORIZATION],
+            ),
         }
     }
 
@@ -65,8 +76,7 @@ impl Client {
     }
 
     pub fn post(&self, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}", self.base_url, path).replace("//", "/");
-        self.http_client.post(url)
+        self.http.post(path)
     }
 
     /// Note: default embedding dimension of 0 will be used if model is not known.
@@ -152,6 +162,8 @@ pub struct EmbeddingResponse {
     pub response_type: Option<String>,
     pub id: String,
     pub embeddings: Vec<Vec<f64>>,
+    /// Texts that were embedded, empty when embedding images
+    #[serde(default)]
     pub texts: Vec<String>,
     #[serde(default)]
     pub meta: Option<Meta>,
@@ -217,19 +229,75 @@ impl embeddings::EmbeddingModel for EmbeddingModel {
     ) -> Result<Vec<embeddings::Embedding>, EmbeddingError> {
         let documents = documents.into_iter().collect::<Vec<_>>();
 
+        let embeddings = self
+            .embed(
+                json!({
+                    "model": self.model,
+                    "texts": documents,
+                    "input_type": self.input_type,
+                }),
+                documents.len(),
+            )
+            .await?;
+
+        Ok(embeddings
+            .into_iter()
+            .zip(documents.into_iter())
+            .map(|(embedding, document)| embeddings::Embedding {
+                document,
+                vec: embedding,
+            })
+            .collect())
+    }
+}
+
+impl embeddings::MultimodalEmbeddingModel for EmbeddingModel {
+    /// Embed an image with the `image` input type, regardless of the input type of the model.
+    /// Only the v3.0 embedding models support images.
+    async fn embed_image(
+        &self,
+        bytes: &[u8],
+        mime: &str,
+    ) -> Result<embeddings::Embedding, EmbeddingError> {
+        let embedding = self
+            .embed(self.image_request(bytes, mime)?, 1)
+            .await?
+            .pop()
+            .expect("There should be exactly one embedding");
+
+        Ok(embeddings::Embedding {
+            document: format!("<{} image, {} bytes>", mime, bytes.len()),
+            vec: embedding,
+        })
+    }
+}
+
+impl EmbeddingModel {
+    pub fn new(client: Client, model: &str, input_type: &str, ndims: usize) -> Self {
+        Self {
+            client,
+            model: model.to_string(),
+            input_type: input_type.to_string(),
+            ndims,
+        }
+    }
+
+    /// Send an embed request and return the embeddings, checking that there are `expected` of them.
+    async fn embed(
+        &self,
+        request: serde_json::Value,
+        expected: usize,
+    ) -> Result<Vec<Vec<f64>>, EmbeddingError> {
         let response = self
             .client
             .post("/v1/embed")
-            .json(&json!({
-                "model": self.model,
-                "texts": documents,
-                "input_type": self.input_type,
-            }))
-            .send()
+            .json_body(&request, self.client.http.gzip_threshold)?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<EmbeddingResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<EmbeddingResponse>>(&body)? {
                 ApiResponse::Ok(response) => {
                     match response.meta {
                         Some(meta) => tracing::info!(target: "rig",
@@ -241,26 +309,18 @@ impl embeddings::EmbeddingModel for EmbeddingModel {
                         ),
                     };
 
-                    if response.embeddings.len() != documents.len() {
+                    if response.embeddings.len() != expected {
                         return Err(EmbeddingError::DocumentError(
                             format!(
                                 "Expected {} embeddings, got {}",
-                                documents.len(),
+                                expected,
                                 response.embeddings.len()
                             )
                             .into(),
                         ));
                     }
 
-                    Ok(response
-                        .embeddings
-                        .into_iter()
-                        .zip(documents.into_iter())
-                        .map(|(embedding, document)| embeddings::Embedding {
-                            document,
-                            vec: embedding,
-                        })
-                        .collect())
+                    Ok(response.embeddings)
                 }
                 ApiResponse::Err(error) => Err(EmbeddingError::ProviderError(error.message)),
             }
@@ -268,16 +328,20 @@ impl embeddings::EmbeddingModel for EmbeddingModel {
             Err(EmbeddingError::ProviderError(response.text().await?))
         }
     }
-}
 
-impl EmbeddingModel {
-    pub fn new(client: Client, model: &str, input_type: &str, ndims: usize) -> Self {
-        Self {
-            client,
-            model: model.to_string(),
-            input_type: input_type.to_string(),
-            ndims,
+    /// Body of an embed request for a single image, sent as a base64 data URI.
+    fn image_request(&self, bytes: &[u8], mime: &str) -> Result<serde_json::Value, EmbeddingError> {
+        if !mime.starts_with("image/") {
+            return Err(EmbeddingError::DocumentError(
+                format!("Unsupported MIME type for an image: {}", mime).into(),
+            ));
         }
+
+        Ok(json!({
+            "model": self.model,
+            "images": [format!("data:{};base64,{}", mime, BASE64_STANDARD.encode(bytes))],
+            "input_type": "image",
+        }))
     }
 }
 
@@ -335,6 +399,7 @@ impl From<CompletionResponse> for completion::CompletionResponse<CompletionRespo
 
         completion::CompletionResponse {
             choice: model_response,
+            logprobs: None,
             raw_response: response,
         }
     }
@@ -529,18 +594,20 @@ impl completion::CompletionModel for CompletionModel {
         let response = self
             .client
             .post("/v1/chat")
-            .json(
+            .json_body(
                 &if let Some(ref params) = completion_request.additional_params {
                     json_utils::merge(request.clone(), params.clone())
                 } else {
                     request.clone()
                 },
-            )
-            .send()
+                self.client.http.gzip_threshold,
+            )?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<CompletionResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<CompletionResponse>>(&body)? {
                 ApiResponse::Ok(completion) => Ok(completion.into()),
                 ApiResponse::Err(error) => Err(CompletionError::ProviderError(error.message)),
             }
@@ -549,3 +616,51 @@ impl completion::CompletionModel for CompletionModel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{ApiResponse, Client, EmbeddingResponse, EMBED_ENGLISH_V3};
+    use crate::embeddings::EmbeddingError;
+
+    #[test]
+    fn test_image_request() {
+        let model = Client::new("test-key").embedding_model(EMBED_ENGLISH_V3, "search_document");
+
+        let request = model.image_request(b"\x89PNG", "image/png").unwrap();
+        assert_eq!(
+            request,
+            json!({
+                "model": EMBED_ENGLISH_V3,
+                "images": ["data:image/png;base64,iVBORw=="],
+                "input_type": "image",
+            })
+        );
+
+        assert!(matches!(
+            model.image_request(b"%PDF", "application/pdf"),
+            Err(EmbeddingError::DocumentError(_))
+        ));
+    }
+
+    #[test]
+    fn test_image_embedding_response() {
+        // Responses to image embed requests have no texts
+        let response: ApiResponse<EmbeddingResponse> = serde_json::from_value(json!({
+            "response_type": "embeddings_floats",
+            "id": "5807ee2e-0cda-445a-9ec8-864c60a06606",
+            "embeddings": [[0.1, 0.2, 0.3]],
+            "images": [{"width": 1, "height": 1, "format": "png", "bit_depth": 8}],
+        }))
+        .unwrap();
+
+        match response {
+            ApiResponse::Ok(response) => {
+                assert!(response.texts.is_empty());
+                assert_eq!(response.embeddings, vec![vec![0.1, 0.2, 0.3]]);
+            }
+            ApiResponse::Err(_) => panic!("Expected a successful response"),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     agent::AgentBuilder,
     embeddings::{self},
     extractor::ExtractorBuilder,
+    providers::http_client::{HttpClient, ProviderClient},
     Embed,
 };
 use schemars::JsonSchema;
@@ -16,9 +17,14 @@ const GEMINI_API_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 
 #[derive(Clone)]
 pub struct Client {
-    base_url: String,
+    pub(crate) http: HttpClient,
     api_key: String,
-    http_client: reqwest::Client,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
 }
 
 impl Client {
@@ -27,19 +33,20 @@ impl Client {
     }
     fn from_url(api_key: &str, base_url: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
-            api_key: api_key.to_string(),
-            http_client: reqwest::Client::builder()
-                .default_headers({
+            http: HttpClient::new(
+                base_url,
+                {
                     let mut headers = reqwest::header::HeaderMap::new();
                     headers.insert(
                         reqwest::header::CONTENT_TYPE,
                         "application/json".parse().unwrap(),
                     );
                     headers
-                })
-                .build()
-                .expect("Gemini reqwest client should build"),
+                },
+                // The API key is sent in the query string, but would be overridden by this header
+                &[reqwest::header::HeaderName::from_static("x-goog-api-key")],
+            ),
+            api_key: api_key.to_string(),
         }
     }
 
@@ -51,10 +58,7 @@ impl Client {
     }
 
     pub fn post(&self, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}?key={}", self.base_url, path, self.api_key).replace("//", "/");
-
-        tracing::debug!("POST {}", url);
-        self.http_client.post(url)
+        self.http.post(path).query(&[("key", &self.api_key)])
     }
 
     /// Create an embedding model with the given name.
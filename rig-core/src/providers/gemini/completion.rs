@@ -19,7 +19,11 @@ use gemini_api_types::{
 use serde_json::{Map, Value};
 use std::convert::TryFrom;
 
-use crate::completion::{self, CompletionError, CompletionRequest};
+use crate::{
+    completion::{self, CompletionError, CompletionRequest},
+    json_utils,
+    providers::{http_client::JsonBody, retry::SendWithRetry},
+};
 
 use super::Client;
 
@@ -115,12 +119,13 @@ impl completion::CompletionModel for CompletionModel {
         let response = self
             .client
             .post(&format!("/v1beta/models/{}:generateContent", self.model))
-            .json(&request)
-            .send()
+            .json_body(&request, self.client.http.gzip_threshold)?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?
-            .error_for_status()?
-            .json::<GenerateContentResponse>()
-            .await?;
+            .error_for_status()?;
+        let response = serde_json::from_str::<GenerateContentResponse>(
+            &json_utils::response_text(response).await?,
+        )?;
 
         match response.usage_metadata {
             Some(ref usage) => tracing::info!(target: "rig",
@@ -176,6 +181,7 @@ impl TryFrom<GenerateContentResponse> for completion::CompletionResponse<Generat
                         ))
                     }
                 },
+                logprobs: None,
                 raw_response: response,
             }),
             _ => Err(CompletionError::ResponseError(
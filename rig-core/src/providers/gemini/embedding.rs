@@ -5,7 +5,11 @@
 
 use serde_json::json;
 
-use crate::embeddings::{self, EmbeddingError};
+use crate::{
+    embeddings::{self, EmbeddingError},
+    json_utils,
+    providers::{http_client::JsonBody, retry::SendWithRetry},
+};
 
 use super::{client::ApiResponse, Client};
 
@@ -60,12 +64,13 @@ impl embeddings::EmbeddingModel for EmbeddingModel {
         let response = self
             .client
             .post(&format!("/v1beta/models/{}:embedContent", self.model))
-            .json(&request_body)
-            .send()
+            .json_body(&request_body, self.client.http.gzip_threshold)?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?
-            .error_for_status()?
-            .json::<ApiResponse<gemini_api_types::EmbeddingResponse>>()
-            .await?;
+            .error_for_status()?;
+        let response = serde_json::from_str::<ApiResponse<gemini_api_types::EmbeddingResponse>>(
+            &json_utils::response_text(response).await?,
+        )?;
 
         match response {
             ApiResponse::Ok(response) => {
@@ -0,0 +1,409 @@
+//! HTTP layer shared by the provider clients.
+//!
+//! Every provider `Client` wraps an [HttpClient], which sends the requests to the provider API,
+//! and implements [ProviderClient], which provides the options common to all providers:
+//! retries, timeouts, custom headers and request compression.
+//!
+//! # Example
+//! ```
+//! use std::time::Duration;
+//! use rig::providers::{openai, retry::RetryPolicy, ProviderClient};
+//!
+//! let openai = openai::Client::new("your-open-ai-api-key")
+//!     .with_retry(RetryPolicy {
+//!         max_retries: 3,
+//!         base_delay: Duration::from_millis(500),
+//!         max_delay: Duration::from_secs(30),
+//!         jitter: true,
+//!     })
+//!     .with_timeout(Duration::from_secs(30))
+//!     .with_header("Helicone-Auth", "Bearer your-helicone-api-key");
+//! ```
+use std::{io::Write, time::Duration};
+
+use flate2::{write::GzEncoder, Compression};
+use reqwest::{
+    header::{HeaderMap, HeaderName},
+    RequestBuilder,
+};
+use serde::Serialize;
+
+use super::retry::RetryPolicy;
+
+/// HTTP client of a provider, holding its base URL, default headers (e.g.: the API key)
+/// and the options set through [ProviderClient].
+#[derive(Clone)]
+pub struct HttpClient {
+    base_url: String,
+    http_client: reqwest::Client,
+    pub(crate) retry_policy: RetryPolicy,
+    timeout: Option<Duration>,
+    /// Custom headers added to every request
+    headers: HeaderMap,
+    /// Headers carrying the provider credentials, which custom headers cannot override
+    auth_headers: Vec<HeaderName>,
+    /// Minimum request body size (in bytes) above which the body is gzip compressed.
+    /// Compression is disabled if `None`.
+    pub(crate) gzip_threshold: Option<usize>,
+}
+
+impl HttpClient {
+    /// Create a new HTTP client sending `default_headers` with every request.
+    /// `auth_headers` are the names of the headers carrying the provider credentials.
+    ///
+    /// Panics if the reqwest client cannot be built (i.e.: if the TLS backend cannot be initialized).
+    pub(crate) fn new(
+        base_url: &str,
+        default_headers: HeaderMap,
+        auth_headers: &[HeaderName],
+    ) -> Self {
+        Self {
+            base_url: base_url.to_string(),
+            http_client: reqwest::Client::builder()
+                .default_headers(default_headers)
+                .build()
+                .expect("reqwest client should build"),
+            retry_policy: RetryPolicy::default(),
+            timeout: None,
+            headers: HeaderMap::new(),
+            auth_headers: auth_headers.to_vec(),
+            gzip_threshold: None,
+        }
+    }
+
+    /// Create a POST request to `path`, relative to the base URL.
+    pub(crate) fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        let url = format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        );
+
+        tracing::debug!(target: "rig", "POST {}", url);
+        let request = self.http_client.post(url).headers(self.headers.clone());
+
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+}
+
+/// Options shared by all provider clients. The trait must be in scope to use them.
+pub trait ProviderClient: Sized {
+    /// The HTTP client used to send requests to the provider.
+    fn http_client_mut(&mut self) -> &mut HttpClient;
+
+    /// Retry requests that fail with a transient error (e.g.: rate limits or server overload)
+    /// according to `policy`. Retries are disabled by default.
+    fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.http_client_mut().retry_policy = policy;
+        self
+    }
+
+    /// Set the timeout of each request sent to the provider, from when the request starts connecting
+    /// until the response body has finished. Requests that time out fail with a
+    /// [reqwest::Error] for which `is_timeout()` is true. There is no timeout by default.
+    fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http_client_mut().timeout = Some(timeout);
+        self
+    }
+
+    /// Enable gzip compression of request bodies larger than `threshold` bytes.
+    /// Compressed requests are sent with the `Content-Encoding: gzip` header.
+    ///
+    /// This is useful when embedding large batches of documents over slow links.
+    /// Compression is disabled by default since not all servers (e.g.: OpenAI-compatible
+    /// or self-hosted ones) support it.
+    ///
+    /// # Example
+    /// ```
+    /// use rig::providers::{openai, ProviderClient};
+    ///
+    /// // Compress request bodies larger than 64KB
+    /// let openai = openai::Client::new("your-open-ai-api-key").with_gzip_compression(64 * 1024);
+    /// ```
+    fn with_gzip_compression(mut self, threshold: usize) -> Self {
+        self.http_client_mut().gzip_threshold = Some(threshold);
+        self
+    }
+
+    /// Add a custom header to every request sent to the provider, e.g.: to authenticate with a gateway
+    /// or proxy. Custom headers replace the default headers of the client with the same name, except
+    /// for the headers carrying the provider credentials (e.g.: `Authorization` for OpenAI or
+    /// `x-api-key` for Anthropic), which are ignored.
+    ///
+    /// Panics if the header name or value is invalid.
+    fn with_header(self, name: &str, value: &str) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            HeaderName::from_bytes(name.as_bytes()).expect("Header name should parse"),
+            value.parse().expect("Header value should parse"),
+        );
+        self.with_headers(headers)
+    }
+
+    /// Add custom headers to every request sent to the provider.
+    /// See [ProviderClient::with_header] for how they are merged with the default headers.
+    fn with_headers(mut self, mut headers: HeaderMap) -> Self {
+        let http_client = self.http_client_mut();
+        for name in &http_client.auth_headers {
+            headers.remove(name);
+        }
+        http_client.headers.extend(headers);
+        self
+    }
+}
+
+/// Set the body of requests to JSON, compressing it according to [HttpClient::gzip_threshold].
+pub(crate) trait JsonBody: Sized {
+    fn json_body<T: Serialize + ?Sized>(
+        self,
+        body: &T,
+        gzip_threshold: Option<usize>,
+    ) -> Result<Self, serde_json::Error>;
+}
+
+impl JsonBody for RequestBuilder {
+    fn json_body<T: Serialize + ?Sized>(
+        self,
+        body: &T,
+        gzip_threshold: Option<usize>,
+    ) -> Result<Self, serde_json::Error> {
+        let body = serde_json::to_vec(body)?;
+        let request = self.header(reqwest::header::CONTENT_TYPE, "application/json");
+
+        match gzip_threshold {
+            Some(threshold) if body.len() > threshold => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(&body)
+                    .expect("Writing to an in-memory buffer should not fail");
+                let compressed = encoder
+                    .finish()
+                    .expect("Writing to an in-memory buffer should not fail");
+
+                Ok(request
+                    .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                    .body(compressed))
+            }
+            _ => Ok(request.body(body)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+    use reqwest::header::HeaderMap;
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    use super::{HttpClient, JsonBody, ProviderClient};
+    use crate::{
+        embeddings::EmbeddingModel,
+        providers::{anthropic, openai},
+    };
+
+    /// Start a local HTTP server answering the first request with `response` as JSON body,
+    /// and return its URL along with the headers and body of the request it received.
+    async fn capture_request(
+        response: &'static str,
+    ) -> (String, oneshot::Receiver<(HeaderMap, Vec<u8>)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 4096];
+            let header_end = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                if let Some(i) = request.windows(4).position(|window| window == b"\r\n\r\n") {
+                    break i + 4;
+                }
+            };
+
+            let headers = String::from_utf8_lossy(&request[..header_end])
+                .lines()
+                .skip(1)
+                .take_while(|line| !line.is_empty())
+                .map(|line| {
+                    let (name, value) = line.split_once(':').unwrap();
+                    (name.parse().unwrap(), value.trim().parse().unwrap())
+                })
+                .collect::<HeaderMap>();
+
+            let content_length = headers
+                .get(reqwest::header::CONTENT_LENGTH)
+                .map(|value| value.to_str().unwrap().parse::<usize>().unwrap())
+                .unwrap_or(0);
+            while request.len() < header_end + content_length {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let _ = tx.send((headers, request[header_end..].to_vec()));
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+
+        (addr, rx)
+    }
+
+    async fn sent_headers(client: impl FnOnce(&str) -> HttpClient) -> HeaderMap {
+        let (addr, request) = capture_request("{}").await;
+        client(&addr).post("/test").send().await.unwrap();
+        request.await.unwrap().0
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers() {
+        let headers = sent_headers(|base_url| {
+            openai::Client::from_url("test-key", base_url)
+                .with_header("Helicone-Auth", "Bearer helicone-key")
+                .with_header("X-Api-Org", "org0")
+                .with_header("X-Api-Org", "org1")
+                .http
+        })
+        .await;
+
+        assert_eq!(headers["helicone-auth"], "Bearer helicone-key");
+        assert_eq!(headers["x-api-org"], "org1");
+        assert_eq!(headers["authorization"], "Bearer test-key");
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_keep_openai_credentials() {
+        let headers = sent_headers(|base_url| {
+            openai::Client::from_url("test-key", base_url)
+                .with_header("Authorization", "Bearer other-key")
+                .http
+        })
+        .await;
+
+        assert_eq!(
+            headers.get_all("authorization").iter().collect::<Vec<_>>(),
+            vec!["Bearer test-key"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_keep_anthropic_credentials() {
+        let headers = sent_headers(|base_url| {
+            anthropic::ClientBuilder::new("test-key")
+                .base_url(base_url)
+                .build()
+                .with_header("x-api-key", "other-key")
+                .with_header("anthropic-version", "2024-01-01")
+                .http
+        })
+        .await;
+
+        assert_eq!(
+            headers.get_all("x-api-key").iter().collect::<Vec<_>>(),
+            vec!["test-key"]
+        );
+        // Headers not carrying credentials can still be overridden
+        assert_eq!(
+            headers
+                .get_all("anthropic-version")
+                .iter()
+                .collect::<Vec<_>>(),
+            vec!["2024-01-01"]
+        );
+    }
+
+    fn test_client() -> HttpClient {
+        HttpClient::new("http://localhost", HeaderMap::new(), &[])
+    }
+
+    #[test]
+    fn test_json_body_uncompressed_by_default() {
+        let client = test_client();
+        let body = json!({"input": ["a".repeat(1024)]});
+
+        let request = client
+            .post("/embeddings")
+            .json_body(&body, client.gzip_threshold)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert!(request.headers().get("content-encoding").is_none());
+        assert_eq!(
+            request.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(
+                request.body().unwrap().as_bytes().unwrap()
+            )
+            .unwrap(),
+            body
+        );
+    }
+
+    #[test]
+    fn test_json_body_gzip_below_threshold() {
+        let request = test_client()
+            .post("/embeddings")
+            .json_body(&json!({"input": ["small"]}), Some(4096))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert!(request.headers().get("content-encoding").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_gzip_compression_round_trip() {
+        let (addr, request) = capture_request(
+            r#"{
+                "object": "list",
+                "data": [{"object": "embedding", "embedding": [1.0, 2.0], "index": 0}],
+                "model": "text-embedding-3-small",
+                "usage": {"prompt_tokens": 1, "total_tokens": 1}
+            }"#,
+        )
+        .await;
+
+        let document = "a".repeat(1024);
+        let embedding = openai::Client::from_url("test-key", &addr)
+            .with_gzip_compression(512)
+            .embedding_model(openai::TEXT_EMBEDDING_3_SMALL)
+            .embed_text(&document)
+            .await
+            .unwrap();
+        assert_eq!(embedding.vec, vec![1.0, 2.0]);
+
+        let (headers, body) = request.await.unwrap();
+        assert_eq!(headers["content-encoding"], "gzip");
+        assert_eq!(headers["content-type"], "application/json");
+        assert!(body.len() < document.len());
+
+        let mut decoded = String::new();
+        GzDecoder::new(body.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&decoded).unwrap(),
+            json!({
+                "model": openai::TEXT_EMBEDDING_3_SMALL,
+                "input": [document],
+            })
+        );
+    }
+}
@@ -6,6 +6,7 @@
 //! - Perplexity
 //! - Anthropic
 //! - Google Gemini
+//! - Ollama (embeddings)
 //!
 //! Each provider has its own module, which contains a `Client` implementation that can
 //! be used to initialize completion and embedding models and execute requests to those models.
@@ -43,6 +44,11 @@
 pub mod anthropic;
 pub mod cohere;
 pub mod gemini;
+pub mod http_client;
+pub mod ollama;
 pub mod openai;
 pub mod perplexity;
+pub mod retry;
 pub mod xai;
+
+pub use http_client::ProviderClient;
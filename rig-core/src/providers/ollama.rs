@@ -0,0 +1,319 @@
+//! Ollama API client and Rig integration
+//!
+//! Ollama also exposes an OpenAI compatible API, so completion models can be used through the
+//! [OpenAI client](crate::providers::openai::Client) (see the `agent_with_ollama` example).
+//! This module provides the embedding models, which use Ollama's native `/api/embeddings` endpoint.
+//!
+//! # Example
+//! ```
+//! use rig::providers::ollama;
+//!
+//! // Connect to a local Ollama server (i.e.: `http://localhost:11434`)
+//! let client = ollama::Client::new();
+//!
+//! let nomic_embed_text = client.embedding_model(ollama::NOMIC_EMBED_TEXT);
+//! ```
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use futures::{stream, StreamExt, TryStreamExt};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::{
+    embeddings::{self, EmbeddingError, EmbeddingsBuilder},
+    json_utils,
+    providers::{
+        http_client::{HttpClient, JsonBody, ProviderClient},
+        retry::SendWithRetry,
+    },
+    Embed,
+};
+
+// ================================================================
+// Main Ollama Client
+// ================================================================
+const OLLAMA_API_BASE_URL: &str = "http://localhost:11434";
+
+#[derive(Clone)]
+pub struct Client {
+    pub(crate) http: HttpClient,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Client {
+    /// Create a new Ollama client connecting to a local Ollama server.
+    pub fn new() -> Self {
+        Self::from_url(OLLAMA_API_BASE_URL)
+    }
+
+    /// Create a new Ollama client from the `OLLAMA_API_BASE_URL` environment variable,
+    /// or connecting to a local Ollama server if it is not set.
+    pub fn from_env() -> Self {
+        match std::env::var("OLLAMA_API_BASE_URL") {
+            Ok(base_url) => Self::from_url(&base_url),
+            Err(_) => Self::new(),
+        }
+    }
+
+    /// Create a new Ollama client connecting to the server at `base_url`
+    /// (e.g.: `http://my-gpu-box:11434`).
+    pub fn from_url(base_url: &str) -> Self {
+        Self {
+            http: HttpClient::new(
+                base_url.trim_end_matches('/'),
+                reqwest::header::HeaderMap::new(),
+                // Ollama has no credentials, so e.g.: a proxy `Authorization` header can be added
+                &[],
+            ),
+        }
+    }
+
+    fn post(&self, path: &str) -> reqwest::RequestBuilder {
+        self.http.post(path)
+    }
+
+    /// Create an embedding model with the given name.
+    /// The number of dimensions of the model is detected from the first successful request,
+    /// and [embeddings::EmbeddingModel::ndims] returns 0 until then. If it is needed beforehand,
+    /// use [Client::embedding_model_with_ndims] instead.
+    ///
+    /// # Example
+    /// ```
+    /// use rig::providers::ollama;
+    ///
+    /// let client = ollama::Client::from_url("http://my-gpu-box:11434");
+    ///
+    /// let embedding_model = client.embedding_model(ollama::NOMIC_EMBED_TEXT);
+    /// ```
+    pub fn embedding_model(&self, model: &str) -> EmbeddingModel {
+        EmbeddingModel::new(self.clone(), model, 0)
+    }
+
+    /// Create an embedding model with the given name and the number of dimensions in the
+    /// embedding generated by the model.
+    pub fn embedding_model_with_ndims(&self, model: &str, ndims: usize) -> EmbeddingModel {
+        EmbeddingModel::new(self.clone(), model, ndims)
+    }
+
+    /// Create an embedding builder with the given embedding model.
+    pub fn embeddings<D: Embed>(&self, model: &str) -> EmbeddingsBuilder<EmbeddingModel, D> {
+        EmbeddingsBuilder::new(self.embedding_model(model))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiErrorResponse {
+    error: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ApiResponse<T> {
+    Ok(T),
+    Err(ApiErrorResponse),
+}
+
+// ================================================================
+// Ollama Embedding API
+// ================================================================
+/// `nomic-embed-text` embedding model
+pub const NOMIC_EMBED_TEXT: &str = "nomic-embed-text";
+/// `mxbai-embed-large` embedding model
+pub const MXBAI_EMBED_LARGE: &str = "mxbai-embed-large";
+/// `all-minilm` embedding model
+pub const ALL_MINILM: &str = "all-minilm";
+
+/// Maximum number of embedding requests sent concurrently by [EmbeddingModel::embed_texts],
+/// since the endpoint only embeds one document per request.
+const MAX_CONCURRENT_REQUESTS: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct EmbeddingResponse {
+    pub embedding: Vec<f64>,
+}
+
+#[derive(Clone)]
+pub struct EmbeddingModel {
+    client: Client,
+    pub model: String,
+    /// Number of dimensions, `0` while unknown. Shared between clones of the model
+    /// so that the value detected by one of them is seen by all of them.
+    ndims: Arc<AtomicUsize>,
+}
+
+impl EmbeddingModel {
+    pub fn new(client: Client, model: &str, ndims: usize) -> Self {
+        Self {
+            client,
+            model: model.to_string(),
+            ndims: Arc::new(AtomicUsize::new(ndims)),
+        }
+    }
+
+    async fn embed_document(
+        &self,
+        document: String,
+    ) -> Result<embeddings::Embedding, EmbeddingError> {
+        let response = self
+            .client
+            .post("/api/embeddings")
+            .json_body(
+                &json!({
+                    "model": self.model,
+                    "prompt": document,
+                }),
+                self.client.http.gzip_threshold,
+            )?
+            .send_with_retry(&self.client.http.retry_policy)
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(EmbeddingError::ProviderError(response.text().await?));
+        }
+
+        let body = json_utils::response_text(response).await?;
+        match serde_json::from_str::<ApiResponse<EmbeddingResponse>>(&body)? {
+            ApiResponse::Ok(response) => {
+                if response.embedding.is_empty() {
+                    return Err(EmbeddingError::ResponseError(format!(
+                        "Empty embedding returned by Ollama model {}",
+                        self.model
+                    )));
+                }
+
+                // Only the first successful request sets the number of dimensions
+                let _ = self.ndims.compare_exchange(
+                    0,
+                    response.embedding.len(),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                );
+
+                Ok(embeddings::Embedding {
+                    document,
+                    vec: response.embedding,
+                })
+            }
+            ApiResponse::Err(err) => Err(EmbeddingError::ProviderError(err.error)),
+        }
+    }
+}
+
+impl embeddings::EmbeddingModel for EmbeddingModel {
+    const MAX_DOCUMENTS: usize = 1024;
+
+    fn ndims(&self) -> usize {
+        self.ndims.load(Ordering::Relaxed)
+    }
+
+    async fn embed_texts(
+        &self,
+        documents: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<embeddings::Embedding>, EmbeddingError> {
+        let documents = documents.into_iter().collect::<Vec<_>>();
+
+        // `buffered` keeps the embeddings in the same order as the documents
+        stream::iter(documents)
+            .map(|document| self.embed_document(document))
+            .buffered(MAX_CONCURRENT_REQUESTS)
+            .try_collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::Client;
+    use crate::embeddings::EmbeddingModel;
+
+    /// Start a local HTTP server answering every request with the embedding `[1.0, 2.0, 3.0]`,
+    /// and checking that it is sent to the Ollama embeddings endpoint.
+    async fn serve() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(async move {
+                    let mut buf = [0; 4096];
+                    let n = socket.read(&mut buf).await.unwrap();
+                    let request = String::from_utf8_lossy(&buf[..n]);
+
+                    let body = if request.starts_with("POST /api/embeddings ")
+                        && request.contains(r#""model":"nomic-embed-text""#)
+                    {
+                        r#"{"embedding":[1.0,2.0,3.0]}"#
+                    } else {
+                        r#"{"error":"unexpected request"}"#
+                    };
+                    let response = format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    );
+                    let _ = socket.write_all(response.as_bytes()).await;
+                });
+            }
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_embed_texts() {
+        let client = Client::from_url(&format!("{}/", serve().await));
+        let model = client.embedding_model(super::NOMIC_EMBED_TEXT);
+        assert_eq!(model.ndims(), 0);
+
+        let embeddings = model
+            .embed_texts(vec![
+                "foo".to_string(),
+                "bar".to_string(),
+                "baz".to_string(),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            embeddings
+                .iter()
+                .map(|embedding| embedding.document.as_str())
+                .collect::<Vec<_>>(),
+            vec!["foo", "bar", "baz"]
+        );
+        assert_eq!(embeddings[0].vec, vec![1.0, 2.0, 3.0]);
+        assert_eq!(model.ndims(), 3);
+
+        let model = client.embedding_model_with_ndims(super::NOMIC_EMBED_TEXT, 768);
+        assert_eq!(model.ndims(), 768);
+    }
+
+    #[tokio::test]
+    async fn test_embed_texts_error() {
+        let client = Client::from_url(&serve().await);
+        let model = client.embedding_model("unknown-model");
+
+        assert!(model.embed_text("foo").await.is_err());
+        assert_eq!(model.ndims(), 0);
+    }
+}
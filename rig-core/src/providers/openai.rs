@@ -13,8 +13,14 @@ use crate::{
     completion::{self, CompletionError, CompletionRequest},
     embeddings::{self, EmbeddingError, EmbeddingsBuilder},
     extractor::ExtractorBuilder,
-    json_utils, Embed,
+    json_utils,
+    providers::{
+        http_client::{HttpClient, JsonBody, ProviderClient},
+        retry::SendWithRetry,
+    },
+    Embed,
 };
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -26,8 +32,13 @@ const OPENAI_API_BASE_URL: &str = "https://api.openai.com/v1";
 
 #[derive(Clone)]
 pub struct Client {
-    base_url: String,
-    http_client: reqwest::Client,
+    pub(crate) http: HttpClient,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
 }
 
 impl Client {
@@ -39,9 +50,9 @@ impl Client {
     /// Create a new OpenAI client with the given API key and base API URL.
     pub fn from_url(api_key: &str, base_url: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
-            http_client: reqwest::Client::builder()
-                .default_headers({
+            http: HttpClient::new(
+                base_url,
+                {
                     let mut headers = reqwest::header::HeaderMap::new();
                     headers.insert(
                         "Authorization",
@@ -50,9 +61,9 @@ impl Client {
                             .expect("Bearer token should parse"),
                     );
                     headers
-                })
-                .build()
-                .expect("OpenAI reqwest client should build"),
+                },
+                &[reqwest::header::AUTHORIZATION],
+            ),
         }
     }
 
@@ -64,8 +75,7 @@ impl Client {
     }
 
     fn post(&self, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}", self.base_url, path).replace("//", "/");
-        self.http_client.post(url)
+        self.http.post(path)
     }
 
     /// Create an embedding model with the given name.
@@ -258,15 +268,19 @@ impl embeddings::EmbeddingModel for EmbeddingModel {
         let response = self
             .client
             .post("/embeddings")
-            .json(&json!({
-                "model": self.model,
-                "input": documents,
-            }))
-            .send()
+            .json_body(
+                &json!({
+                    "model": self.model,
+                    "input": documents,
+                }),
+                self.client.http.gzip_threshold,
+            )?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<EmbeddingResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<EmbeddingResponse>>(&body)? {
                 ApiResponse::Ok(response) => {
                     tracing::info!(target: "rig",
                         "OpenAI embedding token usage: {}",
@@ -374,6 +388,13 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
     type Error = CompletionError;
 
     fn try_from(value: CompletionResponse) -> std::prelude::v1::Result<Self, Self::Error> {
+        let logprobs = value
+            .choices
+            .first()
+            .and_then(|choice| choice.logprobs.as_ref())
+            .and_then(|logprobs| logprobs.content.as_ref())
+            .map(|content| content.iter().map(completion::TokenLogProb::from).collect());
+
         match value.choices.as_slice() {
             [Choice {
                 message:
@@ -392,6 +413,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                         call.function.name.clone(),
                         serde_json::from_str(&call.function.arguments)?,
                     ),
+                    logprobs,
                     raw_response: value,
                 })
             }
@@ -404,6 +426,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 ..
             }, ..] => Ok(completion::CompletionResponse {
                 choice: completion::ModelChoice::Message(content.to_string()),
+                logprobs,
                 raw_response: value,
             }),
             _ => Err(CompletionError::ResponseError(
@@ -417,10 +440,52 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
 pub struct Choice {
     pub index: usize,
     pub message: Message,
-    pub logprobs: Option<serde_json::Value>,
+    pub logprobs: Option<LogProbs>,
     pub finish_reason: String,
 }
 
+/// Log probability information of a [Choice].
+/// Only returned if requested with [completion::CompletionRequestBuilder::logprobs].
+#[derive(Debug, Deserialize)]
+pub struct LogProbs {
+    /// Log probability information of the message content tokens
+    pub content: Option<Vec<TokenLogProb>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenLogProb {
+    pub token: String,
+    pub logprob: f64,
+    pub bytes: Option<Vec<u8>>,
+    /// Most likely tokens at this position. Empty unless `top_logprobs` was set in the request.
+    #[serde(default)]
+    pub top_logprobs: Vec<TopLogProb>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TopLogProb {
+    pub token: String,
+    pub logprob: f64,
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl From<&TokenLogProb> for completion::TokenLogProb {
+    fn from(logprob: &TokenLogProb) -> Self {
+        Self {
+            token: logprob.token.clone(),
+            logprob: logprob.logprob,
+            top_logprobs: logprob
+                .top_logprobs
+                .iter()
+                .map(|top| completion::TopLogProb {
+                    token: top.token.clone(),
+                    logprob: top.logprob,
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Message {
     pub role: String,
@@ -501,7 +566,7 @@ impl completion::CompletionModel for CompletionModel {
             content: prompt_with_context,
         });
 
-        let request = if completion_request.tools.is_empty() {
+        let mut request = if completion_request.tools.is_empty() {
             json!({
                 "model": self.model,
                 "messages": full_history,
@@ -517,21 +582,31 @@ impl completion::CompletionModel for CompletionModel {
             })
         };
 
+        // Only sent when set, since some OpenAI-compatible servers reject `null` values
+        if let Some(logprobs) = completion_request.logprobs {
+            request["logprobs"] = json!(logprobs);
+        }
+        if let Some(top_logprobs) = completion_request.top_logprobs {
+            request["top_logprobs"] = json!(top_logprobs);
+        }
+
         let response = self
             .client
             .post("/chat/completions")
-            .json(
+            .json_body(
                 &if let Some(params) = completion_request.additional_params {
                     json_utils::merge(request, params)
                 } else {
                     request
                 },
-            )
-            .send()
+                self.client.http.gzip_threshold,
+            )?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<CompletionResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<CompletionResponse>>(&body)? {
                 ApiResponse::Ok(response) => {
                     tracing::info!(target: "rig",
                         "OpenAI completion token usage: {:?}",
@@ -546,3 +621,172 @@ impl completion::CompletionModel for CompletionModel {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::oneshot,
+    };
+
+    use super::Client;
+    use crate::{
+        completion::{CompletionModel, TokenLogProb, TopLogProb},
+        embeddings::{EmbeddingError, EmbeddingModel},
+        providers::ProviderClient,
+    };
+
+    /// Start a local HTTP server answering the first request with a completion containing log
+    /// probabilities, and return its URL along with the JSON body of the request it received.
+    async fn serve_completion() -> (String, oneshot::Receiver<serde_json::Value>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = vec![];
+            let mut buf = [0; 4096];
+            // The body is a single JSON object, so it is complete once it parses
+            let body = loop {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&request);
+                if let Some((_, body)) = text.split_once("\r\n\r\n") {
+                    if let Ok(body) = serde_json::from_str::<serde_json::Value>(body) {
+                        break body;
+                    }
+                }
+            };
+            let _ = tx.send(body);
+
+            let body = json!({
+                "id": "chatcmpl-123",
+                "object": "chat.completion",
+                "created": 1677652288,
+                "model": "gpt-4o",
+                "system_fingerprint": null,
+                "choices": [{
+                    "index": 0,
+                    "message": {
+                        "role": "assistant",
+                        "content": "Yes"
+                    },
+                    "logprobs": {
+                        "content": [{
+                            "token": "Yes",
+                            "logprob": -0.0001,
+                            "bytes": [89, 101, 115],
+                            "top_logprobs": [
+                                {"token": "Yes", "logprob": -0.0001, "bytes": [89, 101, 115]},
+                                {"token": "No", "logprob": -9.2, "bytes": [78, 111]}
+                            ]
+                        }]
+                    },
+                    "finish_reason": "stop"
+                }],
+                "usage": null
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+        });
+
+        (addr, rx)
+    }
+
+    #[tokio::test]
+    async fn test_completion_logprobs() {
+        let (addr, request) = serve_completion().await;
+
+        let response = Client::from_url("test-key", &addr)
+            .completion_model(super::GPT_4O)
+            .completion_request("Is Paris the capital of France?")
+            .logprobs(true)
+            .top_logprobs(2)
+            .send()
+            .await
+            .unwrap();
+
+        let request = request.await.unwrap();
+        assert_eq!(request["logprobs"], json!(true));
+        assert_eq!(request["top_logprobs"], json!(2));
+
+        assert_eq!(
+            response.logprobs,
+            Some(vec![TokenLogProb {
+                token: "Yes".into(),
+                logprob: -0.0001,
+                top_logprobs: vec![
+                    TopLogProb {
+                        token: "Yes".into(),
+                        logprob: -0.0001,
+                    },
+                    TopLogProb {
+                        token: "No".into(),
+                        logprob: -9.2,
+                    },
+                ],
+            }])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_completion_without_logprobs() {
+        let (addr, request) = serve_completion().await;
+
+        Client::from_url("test-key", &addr)
+            .completion_model(super::GPT_4O)
+            .completion_request("Is Paris the capital of France?")
+            .send()
+            .await
+            .unwrap();
+
+        let request = request.await.unwrap();
+        assert!(request.get("logprobs").is_none());
+        assert!(request.get("top_logprobs").is_none());
+    }
+
+    #[test]
+    fn test_request_timeout() {
+        let request = Client::new("test-key").post("/embeddings").build().unwrap();
+        assert!(request.timeout().is_none());
+
+        let request = Client::new("test-key")
+            .with_timeout(Duration::from_secs(30))
+            .post("/embeddings")
+            .build()
+            .unwrap();
+        assert_eq!(request.timeout(), Some(&Duration::from_secs(30)));
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_fires() {
+        // Server accepting connections but never responding
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut sockets = vec![];
+            loop {
+                sockets.push(listener.accept().await.unwrap());
+            }
+        });
+
+        let model = Client::from_url("test-key", &base_url)
+            .with_timeout(Duration::from_millis(100))
+            .embedding_model(super::TEXT_EMBEDDING_3_SMALL);
+
+        match model.embed_text("hello").await {
+            Err(EmbeddingError::HttpError(e)) => assert!(e.is_timeout()),
+            result => panic!("Expected a timeout error, got {:?}", result.map(|_| ())),
+        }
+    }
+}
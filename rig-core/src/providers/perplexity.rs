@@ -14,6 +14,10 @@ use crate::{
     completion::{self, CompletionError},
     extractor::ExtractorBuilder,
     json_utils,
+    providers::{
+        http_client::{HttpClient, JsonBody, ProviderClient},
+        retry::SendWithRetry,
+    },
 };
 
 use schemars::JsonSchema;
@@ -27,8 +31,13 @@ const PERPLEXITY_API_BASE_URL: &str = "https://api.perplexity.ai";
 
 #[derive(Clone)]
 pub struct Client {
-    base_url: String,
-    http_client: reqwest::Client,
+    pub(crate) http: HttpClient,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
 }
 
 impl Client {
@@ -45,9 +54,9 @@ impl Client {
 
     pub fn from_url(api_key: &str, base_url: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
-            http_client: reqwest::Client::builder()
-                .default_headers({
+            http: HttpClient::new(
+                base_url,
+                {
                     let mut headers = reqwest::header::HeaderMap::new();
                     headers.insert(
                         "Authorization",
@@ -56,15 +65,14 @@ impl Client {
                             .expect("Bearer token should parse"),
                     );
                     headers
-                })
-                .build()
-                .expect("Perplexity reqwest client should build"),
+                },
+                &[reqwest::header::AUTHORIZATION],
+            ),
         }
     }
 
     pub fn post(&self, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}", self.base_url, path).replace("//", "/");
-        self.http_client.post(url)
+        self.http.post(path)
     }
 
     pub fn completion_model(&self, model: &str) -> CompletionModel {
@@ -171,6 +179,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 ..
             }, ..] => Ok(completion::CompletionResponse {
                 choice: completion::ModelChoice::Message(content.to_string()),
+                logprobs: None,
                 raw_response: value,
             }),
             _ => Err(CompletionError::ResponseError(
@@ -233,18 +242,20 @@ impl completion::CompletionModel for CompletionModel {
         let response = self
             .client
             .post("/chat/completions")
-            .json(
+            .json_body(
                 &if let Some(ref params) = completion_request.additional_params {
                     json_utils::merge(request.clone(), params.clone())
                 } else {
                     request.clone()
                 },
-            )
-            .send()
+                self.client.http.gzip_threshold,
+            )?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<CompletionResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<CompletionResponse>>(&body)? {
                 ApiResponse::Ok(completion) => {
                     tracing::info!(target: "rig",
                         "Perplexity completion token usage: {}",
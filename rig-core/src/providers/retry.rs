@@ -0,0 +1,328 @@
+//! Retry policy for transient errors returned by provider APIs.
+//!
+//! All provider clients accept a [RetryPolicy] through
+//! [ProviderClient::with_retry](super::ProviderClient::with_retry).
+//! Retries are disabled by default.
+//!
+//! # Example
+//! ```
+//! use std::time::Duration;
+//! use rig::providers::{openai, retry::RetryPolicy, ProviderClient};
+//!
+//! let openai = openai::Client::new("your-open-ai-api-key").with_retry(RetryPolicy {
+//!     max_retries: 3,
+//!     base_delay: Duration::from_millis(500),
+//!     max_delay: Duration::from_secs(30),
+//!     jitter: true,
+//! });
+//! ```
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+
+/// Policy used to retry requests that failed with a transient error, i.e.: a `429`, `500`, `502`,
+/// `503` or `504` status code, or a connection error.
+///
+/// The delay before retry `i` (starting at 0) is `base_delay * 2^i`, unless the response has a
+/// `Retry-After` header (in seconds), in which case that delay is used instead. Either way, the
+/// delay is capped at `max_delay`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Maximum number of retries after the first attempt. `0` disables retries.
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Maximum delay before a retry, so that a large `Retry-After` header (or many retries)
+    /// cannot stall a request indefinitely.
+    pub max_delay: Duration,
+    /// Randomize each delay between half and all of its value, to avoid many clients
+    /// retrying at the same time.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry `attempt` (starting at 0), ignoring any `Retry-After` header.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.min(16)));
+
+        if self.jitter {
+            // Random factor in [0.5, 1.0)
+            let random = RandomState::new().build_hasher().finish();
+            delay.mul_f64(0.5 + (random % 1000) as f64 / 2000.0)
+        } else {
+            delay
+        }
+    }
+
+    /// Delay before retry `attempt` (starting at 0), given the `Retry-After` delay of the response, if any.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        retry_after
+            .unwrap_or_else(|| self.backoff(attempt))
+            .min(self.max_delay)
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Value of the `Retry-After` header of the response, if given in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Send requests while retrying transient errors according to a [RetryPolicy].
+pub(crate) trait SendWithRetry {
+    fn send_with_retry(
+        self,
+        policy: &RetryPolicy,
+    ) -> impl std::future::Future<Output = Result<Response, reqwest::Error>> + Send;
+}
+
+impl SendWithRetry for RequestBuilder {
+    async fn send_with_retry(self, policy: &RetryPolicy) -> Result<Response, reqwest::Error> {
+        let mut attempt = 0;
+        let mut request = self;
+
+        loop {
+            // Requests with a streaming body cannot be cloned, and thus cannot be retried
+            let retry_request = match attempt < policy.max_retries {
+                true => request.try_clone(),
+                false => None,
+            };
+
+            let result = request.send().await;
+
+            let Some(retry_request) = retry_request else {
+                return result;
+            };
+
+            let delay = match &result {
+                Ok(response) if is_transient_status(response.status()) => {
+                    policy.delay(attempt, retry_after(response))
+                }
+                Err(e) if e.is_connect() || e.is_request() => policy.delay(attempt, None),
+                _ => return result,
+            };
+
+            tracing::warn!(target: "rig",
+                "Transient error from provider ({}), retrying in {:?} ({}/{})",
+                match &result {
+                    Ok(response) => response.status().to_string(),
+                    Err(e) => e.to_string(),
+                },
+                delay,
+                attempt + 1,
+                policy.max_retries
+            );
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+            request = retry_request;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::{RetryPolicy, SendWithRetry};
+
+    /// Start a local HTTP server answering with the given status codes, in order,
+    /// and then `200 OK`. Returns its address and the number of requests received.
+    async fn serve(statuses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        serve_with_retry_after(statuses, 0).await
+    }
+
+    /// Same as [serve], with a `Retry-After` header of `retry_after` seconds.
+    async fn serve_with_retry_after(
+        statuses: Vec<&'static str>,
+        retry_after: u64,
+    ) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let i = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses.get(i).copied().unwrap_or("200 OK");
+
+                let mut buf = [0; 4096];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nretry-after: {retry_after}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (addr, requests)
+    }
+
+    fn policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_secs(1),
+            jitter: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retry_transient_errors() {
+        let (addr, requests) =
+            serve(vec!["503 Service Unavailable", "429 Too Many Requests"]).await;
+
+        let response = reqwest::Client::new()
+            .post(&addr)
+            .json(&serde_json::json!({"prompt": "hello"}))
+            .send_with_retry(&policy(3))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_limits() {
+        // Retries are disabled by default
+        let (addr, requests) = serve(vec!["503 Service Unavailable"]).await;
+        let response = reqwest::Client::new()
+            .post(&addr)
+            .send_with_retry(&RetryPolicy::default())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // The last response is returned once retries are exhausted
+        let (addr, requests) = serve(vec!["502 Bad Gateway"; 5]).await;
+        let response = reqwest::Client::new()
+            .post(&addr)
+            .send_with_retry(&policy(2))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 502);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Non-transient errors are not retried
+        let (addr, requests) = serve(vec!["400 Bad Request"]).await;
+        let response = reqwest::Client::new()
+            .post(&addr)
+            .send_with_retry(&policy(2))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(60),
+            jitter: false,
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+
+        let policy = RetryPolicy {
+            jitter: true,
+            ..policy
+        };
+        let delay = policy.backoff(1);
+        assert!(delay >= Duration::from_millis(100) && delay < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_max_delay() {
+        let policy = RetryPolicy {
+            max_retries: 20,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(5),
+            jitter: false,
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(200));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(2))),
+            Duration::from_secs(2)
+        );
+
+        // Both the backoff and the `Retry-After` delay are capped
+        assert_eq!(policy.delay(16, None), Duration::from_secs(5));
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(86400))),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_capped() {
+        let (addr, requests) = serve_with_retry_after(vec!["429 Too Many Requests"], 86400).await;
+
+        let response = tokio::time::timeout(
+            Duration::from_secs(5),
+            reqwest::Client::new()
+                .post(&addr)
+                .send_with_retry(&RetryPolicy {
+                    max_delay: Duration::from_millis(10),
+                    ..policy(1)
+                }),
+        )
+        .await
+        .expect("Retry-After delay should be capped")
+        .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+}
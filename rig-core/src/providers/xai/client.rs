@@ -2,6 +2,7 @@ use crate::{
     agent::AgentBuilder,
     embeddings::{self},
     extractor::ExtractorBuilder,
+    providers::http_client::{HttpClient, ProviderClient},
     Embed,
 };
 use schemars::JsonSchema;
@@ -16,8 +17,13 @@ const XAI_BASE_URL: &str = "https://api.x.ai";
 
 #[derive(Clone)]
 pub struct Client {
-    base_url: String,
-    http_client: reqwest::Client,
+    pub(crate) http: HttpClient,
+}
+
+impl ProviderClient for Client {
+    fn http_client_mut(&mut self) -> &mut HttpClient {
+        &mut self.http
+    }
 }
 
 impl Client {
@@ -26,9 +32,9 @@ impl Client {
     }
     fn from_url(api_key: &str, base_url: &str) -> Self {
         Self {
-            base_url: base_url.to_string(),
-            http_client: reqwest::Client::builder()
-                .default_headers({
+            http: HttpClient::new(
+                base_url,
+                {
                     let mut headers = reqwest::header::HeaderMap::new();
                     headers.insert(
                         reqwest::header::CONTENT_TYPE,
@@ -41,9 +47,9 @@ impl Client {
                             .expect("Bearer token should parse"),
                     );
                     headers
-                })
-                .build()
-                .expect("xAI reqwest client should build"),
+                },
+                &[reqwest::header::AUTHORIZATION],
+            ),
         }
     }
 
@@ -55,10 +61,7 @@ impl Client {
     }
 
     pub fn post(&self, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}", self.base_url, path).replace("//", "/");
-
-        tracing::debug!("POST {}", url);
-        self.http_client.post(url)
+        self.http.post(path)
     }
 
     /// Create an embedding model with the given name.
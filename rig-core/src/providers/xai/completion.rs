@@ -6,6 +6,7 @@
 use crate::{
     completion::{self, CompletionError},
     json_utils,
+    providers::{http_client::JsonBody, retry::SendWithRetry},
 };
 
 use serde_json::json;
@@ -84,12 +85,13 @@ impl completion::CompletionModel for CompletionModel {
         let response = self
             .client
             .post("/v1/chat/completions")
-            .json(&request)
-            .send()
+            .json_body(&request, self.client.http.gzip_threshold)?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<CompletionResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<CompletionResponse>>(&body)? {
                 ApiResponse::Ok(completion) => completion.try_into(),
                 ApiResponse::Error(error) => Err(CompletionError::ProviderError(error.message())),
             }
@@ -118,6 +120,7 @@ pub mod xai_api_types {
                     ..
                 }, ..] => Ok(completion::CompletionResponse {
                     choice: completion::ModelChoice::Message(content.to_string()),
+                    logprobs: None,
                     raw_response: value,
                 }),
                 [Choice {
@@ -137,6 +140,7 @@ pub mod xai_api_types {
                             call.function.name.clone(),
                             serde_json::from_str(&call.function.arguments)?,
                         ),
+                        logprobs: None,
                         raw_response: value,
                     })
                 }
@@ -6,7 +6,11 @@
 use serde::Deserialize;
 use serde_json::json;
 
-use crate::embeddings::{self, EmbeddingError};
+use crate::{
+    embeddings::{self, EmbeddingError},
+    json_utils,
+    providers::{http_client::JsonBody, retry::SendWithRetry},
+};
 
 use super::{
     client::xai_api_types::{ApiErrorResponse, ApiResponse},
@@ -78,15 +82,19 @@ impl embeddings::EmbeddingModel for EmbeddingModel {
         let response = self
             .client
             .post("/v1/embeddings")
-            .json(&json!({
-                "model": self.model,
-                "input": documents,
-            }))
-            .send()
+            .json_body(
+                &json!({
+                    "model": self.model,
+                    "input": documents,
+                }),
+                self.client.http.gzip_threshold,
+            )?
+            .send_with_retry(&self.client.http.retry_policy)
             .await?;
 
         if response.status().is_success() {
-            match response.json::<ApiResponse<EmbeddingResponse>>().await? {
+            let body = json_utils::response_text(response).await?;
+            match serde_json::from_str::<ApiResponse<EmbeddingResponse>>(&body)? {
                 ApiResponse::Ok(response) => {
                     if response.data.len() != documents.len() {
                         return Err(EmbeddingError::ResponseError(
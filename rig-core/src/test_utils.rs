@@ -0,0 +1,47 @@
+//! Fixtures shared by the unit tests of the crate.
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use crate::embeddings::{Embedding, EmbeddingError, EmbeddingModel};
+
+/// Embedding model embedding texts mentioning "linglingdong" as `[0.0, 1.0]` and all other
+/// texts as `[1.0, 0.0]`, counting the number of calls to `embed_texts`.
+#[derive(Clone, Default)]
+pub(crate) struct MockEmbeddingModel {
+    calls: Arc<AtomicUsize>,
+}
+
+impl MockEmbeddingModel {
+    /// Number of calls to `embed_texts` so far, shared between clones of the model.
+    pub(crate) fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+}
+
+impl EmbeddingModel for MockEmbeddingModel {
+    const MAX_DOCUMENTS: usize = 16;
+
+    fn ndims(&self) -> usize {
+        2
+    }
+
+    async fn embed_texts(
+        &self,
+        documents: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        Ok(documents
+            .into_iter()
+            .map(|document| Embedding {
+                vec: if document.contains("linglingdong") {
+                    vec![0.0, 1.0]
+                } else {
+                    vec![1.0, 0.0]
+                },
+                document,
+            })
+            .collect())
+    }
+}
@@ -2,12 +2,15 @@
 use std::{
     cmp::Reverse,
     collections::{BinaryHeap, HashMap},
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Write},
+    path::Path,
 };
 
 use ordered_float::OrderedFloat;
 use serde::{Deserialize, Serialize};
 
-use super::{VectorStoreError, VectorStoreIndex};
+use super::{check_duplicate_ids, VectorStoreError, VectorStoreIndex};
 use crate::{
     embeddings::{distance::VectorDistance, Embedding, EmbeddingModel},
     OneOrMany,
@@ -122,13 +125,24 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
     }
 
     /// Add documents and their corresponding embeddings to the store with ids.
+    /// Documents with the id of a document already in the store replace it.
+    ///
+    /// Returns a [VectorStoreError::DuplicateIdsError] without adding anything if the batch
+    /// contains the same id more than once.
     pub fn add_documents_with_ids(
         &mut self,
         documents: impl IntoIterator<Item = (impl ToString, D, OneOrMany<Embedding>)>,
-    ) {
-        documents.into_iter().for_each(|(id, doc, embeddings)| {
-            self.embeddings.insert(id.to_string(), (doc, embeddings));
-        });
+    ) -> Result<(), VectorStoreError> {
+        let documents = documents
+            .into_iter()
+            .map(|(id, doc, embeddings)| (id.to_string(), doc, embeddings))
+            .collect::<Vec<_>>();
+        check_duplicate_ids(documents.iter().map(|(id, _, _)| id))?;
+
+        for (id, doc, embeddings) in documents {
+            self.embeddings.insert(id, (doc, embeddings));
+        }
+        Ok(())
     }
 
     /// Add documents and their corresponding embeddings to the store.
@@ -157,6 +171,79 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
     }
 }
 
+/// A single line of an NDJSON file read by [InMemoryVectorStore::from_ndjson]
+/// and written by [InMemoryVectorStore::save_ndjson].
+#[derive(Serialize, Deserialize)]
+struct NdjsonRecord<D> {
+    id: String,
+    document: D,
+    embeddings: Vec<Embedding>,
+}
+
+impl<D: Serialize + for<'a> Deserialize<'a>> InMemoryVectorStore<D> {
+    /// Load a [InMemoryVectorStore] from a newline-delimited JSON file.
+    /// Each line must be an object of the form `{"id": ..., "document": ..., "embeddings": [...]}`,
+    /// where `embeddings` is a non-empty list of [Embedding]. Empty lines are ignored.
+    ///
+    /// This is useful to run RAG tests and evals against a committed fixture instead of a live database.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rig::vector_store::in_memory_store::InMemoryVectorStore;
+    ///
+    /// let vector_store = InMemoryVectorStore::<String>::from_ndjson("tests/data/vector_store.ndjson")?;
+    /// let index = vector_store.index(embedding_model);
+    /// ```
+    pub fn from_ndjson(path: impl AsRef<Path>) -> Result<Self, VectorStoreError> {
+        let file = File::open(path).map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        let mut embeddings = HashMap::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let record: NdjsonRecord<D> = serde_json::from_str(&line)?;
+            let record_embeddings = OneOrMany::many(record.embeddings)
+                .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+            embeddings.insert(record.id, (record.document, record_embeddings));
+        }
+
+        Ok(Self { embeddings })
+    }
+
+    /// Write the documents and embeddings of the store to a newline-delimited JSON file
+    /// that can be loaded back with [InMemoryVectorStore::from_ndjson].
+    /// Records are written sorted by id so that the output is deterministic.
+    pub fn save_ndjson(&self, path: impl AsRef<Path>) -> Result<(), VectorStoreError> {
+        let file = File::create(path).map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+        let mut writer = BufWriter::new(file);
+
+        let mut ids = self.embeddings.keys().collect::<Vec<_>>();
+        ids.sort();
+
+        for id in ids {
+            let (document, embeddings) = &self.embeddings[id];
+            let record = NdjsonRecord {
+                id: id.clone(),
+                document,
+                embeddings: embeddings.iter().cloned().collect(),
+            };
+
+            serde_json::to_writer(&mut writer, &record)?;
+            writer
+                .write_all(b"\n")
+                .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+        }
+
+        writer
+            .flush()
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+    }
+}
+
 /// RankingItem(distance, document_id, serializable document, embeddings document)
 #[derive(Eq, PartialEq)]
 struct RankingItem<'a, D: Serialize>(OrderedFloat<f64>, &'a String, &'a D, &'a String);
@@ -257,16 +344,172 @@ impl<M: EmbeddingModel + Sync, D: Serialize + Sync + Send + Eq> VectorStoreIndex
             .map(|Reverse(RankingItem(distance, id, _, _))| Ok((distance.0, id.clone())))
             .collect::<Result<Vec<_>, _>>()
     }
+
+    /// All queries are embedded in a single call to the embedding model (batched according to
+    /// [EmbeddingModel::MAX_DOCUMENTS]) instead of one call per query.
+    async fn top_n_multi<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        queries: &[&str],
+        n: usize,
+    ) -> Result<Vec<Vec<(f64, String, T)>>, VectorStoreError> {
+        let mut prompt_embeddings = Vec::with_capacity(queries.len());
+        for chunk in queries.chunks(M::MAX_DOCUMENTS) {
+            prompt_embeddings.extend(
+                self.model
+                    .embed_texts(chunk.iter().map(|query| query.to_string()))
+                    .await?,
+            );
+        }
+
+        prompt_embeddings
+            .iter()
+            .map(|prompt_embedding| {
+                self.store
+                    .vector_search(prompt_embedding, n)
+                    .into_sorted_vec()
+                    .into_iter()
+                    .map(|Reverse(RankingItem(distance, id, doc, _))| {
+                        Ok((
+                            distance.0,
+                            id.clone(),
+                            serde_json::from_str(&serde_json::to_string(doc)?)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>, VectorStoreError>>()
+            })
+            .collect()
+    }
+
+    async fn count(&self) -> Result<u64, VectorStoreError> {
+        Ok(self.len() as u64)
+    }
+
+    async fn get_by_id<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        id: &str,
+    ) -> Result<Option<T>, VectorStoreError> {
+        self.store.get_document(id)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::cmp::Reverse;
 
-    use crate::{embeddings::embedding::Embedding, OneOrMany};
+    use crate::{
+        embeddings::embedding::Embedding,
+        test_utils::MockEmbeddingModel,
+        vector_store::{VectorStoreError, VectorStoreIndex},
+        OneOrMany,
+    };
 
     use super::{InMemoryVectorStore, RankingItem};
 
+    #[tokio::test]
+    async fn test_top_n_multi() {
+        let vector_store = InMemoryVectorStore::from_documents_with_ids(vec![
+            (
+                "doc_a",
+                "a".to_string(),
+                OneOrMany::one(Embedding {
+                    document: "a".to_string(),
+                    vec: vec![1.0, 0.0],
+                }),
+            ),
+            (
+                "doc_b",
+                "linglingdong".to_string(),
+                OneOrMany::one(Embedding {
+                    document: "linglingdong".to_string(),
+                    vec: vec![0.0, 1.0],
+                }),
+            ),
+        ]);
+
+        let model = MockEmbeddingModel::default();
+        let index = vector_store.index(model.clone());
+
+        let results = index
+            .top_n_multi::<String>(&["a", "linglingdong", "a", "linglingdong", "a"], 1)
+            .await
+            .unwrap();
+
+        // All queries are embedded in a single call
+        assert_eq!(model.calls(), 1);
+        assert_eq!(
+            results
+                .into_iter()
+                .map(|result| result.into_iter().map(|(_, id, _)| id).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![
+                vec!["doc_a"],
+                vec!["doc_b"],
+                vec!["doc_a"],
+                vec!["doc_b"],
+                vec!["doc_a"]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_from_ndjson() {
+        let vector_store =
+            InMemoryVectorStore::<String>::from_ndjson("tests/data/vector_store.ndjson").unwrap();
+
+        assert_eq!(vector_store.len(), 3);
+        assert_eq!(
+            vector_store.get_document::<String>("doc1").unwrap(),
+            Some("marble-marble".to_string())
+        );
+
+        let ranking = vector_store.vector_search(
+            &Embedding {
+                document: "glarby-glarble".to_string(),
+                vec: vec![0.0, 0.1, 0.6],
+            },
+            1,
+        );
+
+        assert_eq!(
+            ranking
+                .into_iter()
+                .map(|Reverse(RankingItem(distance, id, doc, _))| (
+                    distance.0,
+                    id.clone(),
+                    doc.clone()
+                ))
+                .collect::<Vec<_>>(),
+            vec![(
+                0.9807965956109156,
+                "doc0".to_string(),
+                "glarb-garb".to_string()
+            )]
+        )
+    }
+
+    #[test]
+    fn test_ndjson_round_trip() {
+        let temp = assert_fs::TempDir::new().unwrap();
+        let path = temp.path().join("store.ndjson");
+
+        let vector_store =
+            InMemoryVectorStore::<String>::from_ndjson("tests/data/vector_store.ndjson").unwrap();
+        vector_store.save_ndjson(&path).unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            std::fs::read_to_string("tests/data/vector_store.ndjson").unwrap()
+        );
+
+        let reloaded = InMemoryVectorStore::<String>::from_ndjson(&path).unwrap();
+        let mut original = vector_store.embeddings.into_iter().collect::<Vec<_>>();
+        let mut reloaded = reloaded.embeddings.into_iter().collect::<Vec<_>>();
+        original.sort_by_key(|(id, _)| id.clone());
+        reloaded.sort_by_key(|(id, _)| id.clone());
+
+        assert_eq!(original, reloaded);
+    }
+
     #[test]
     fn test_auto_ids() {
         let mut vector_store = InMemoryVectorStore::from_documents(vec![
@@ -370,6 +613,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_add_documents_with_duplicate_ids() {
+        let embedding = |document: &str| {
+            OneOrMany::one(Embedding {
+                document: document.to_string(),
+                vec: vec![0.1, 0.1, 0.5],
+            })
+        };
+
+        let mut vector_store = InMemoryVectorStore::from_documents_with_ids(vec![(
+            "doc0",
+            "glarb-garb",
+            embedding("glarb-garb"),
+        )]);
+
+        let result = vector_store.add_documents_with_ids(vec![
+            ("doc1", "marble-marble", embedding("marble-marble")),
+            ("doc0", "flumb-flumb", embedding("flumb-flumb")),
+            ("doc1", "brotato", embedding("brotato")),
+        ]);
+        assert!(matches!(
+            result,
+            Err(VectorStoreError::DuplicateIdsError(ids)) if ids == vec!["doc1"]
+        ));
+
+        // Nothing is added (or replaced) if the batch is rejected
+        assert_eq!(vector_store.embeddings.len(), 1);
+        assert_eq!(
+            vector_store.get_document::<String>("doc0").unwrap(),
+            Some("glarb-garb".to_string())
+        );
+
+        // Ids of documents already in the store are replaced
+        vector_store
+            .add_documents_with_ids(vec![("doc0", "flumb-flumb", embedding("flumb-flumb"))])
+            .unwrap();
+        assert_eq!(
+            vector_store.get_document::<String>("doc0").unwrap(),
+            Some("flumb-flumb".to_string())
+        );
+    }
+
     #[test]
     fn test_single_embedding() {
         let vector_store = InMemoryVectorStore::from_documents_with_ids(vec![
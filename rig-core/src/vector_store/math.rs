@@ -0,0 +1,83 @@
+//! Vector math kernels shared by the brute-force vector stores (e.g.: [super::in_memory_store])
+//! and by the [VectorDistance](crate::embeddings::distance::VectorDistance) implementation.
+
+/// Number of independent accumulators used by [fused_cosine_similarity].
+/// Splitting the sums breaks the dependency chain between iterations, which lets the compiler
+/// vectorize the loop and keeps the summation error lower than a single running sum.
+const LANES: usize = 4;
+
+/// Compute the cosine similarity of two vectors in a single pass.
+///
+/// The dot product and both squared magnitudes are accumulated together so each vector
+/// is only read once. If either vector has a magnitude of zero, `0.0` is returned instead of `NaN`.
+pub fn fused_cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let mut dot = [0.0; LANES];
+    let mut norm_a = [0.0; LANES];
+    let mut norm_b = [0.0; LANES];
+
+    let chunks_a = a.chunks_exact(LANES);
+    let chunks_b = b.chunks_exact(LANES);
+    let (rem_a, rem_b) = (chunks_a.remainder(), chunks_b.remainder());
+
+    for (x, y) in chunks_a.zip(chunks_b) {
+        for i in 0..LANES {
+            dot[i] += x[i] * y[i];
+            norm_a[i] += x[i] * x[i];
+            norm_b[i] += y[i] * y[i];
+        }
+    }
+
+    for (i, (x, y)) in rem_a.iter().zip(rem_b.iter()).enumerate() {
+        dot[i] += x * y;
+        norm_a[i] += x * x;
+        norm_b[i] += y * y;
+    }
+
+    let dot: f64 = dot.iter().sum();
+    let magnitude = norm_a.iter().sum::<f64>().sqrt() * norm_b.iter().sum::<f64>().sqrt();
+
+    if magnitude == 0.0 {
+        0.0
+    } else {
+        dot / magnitude
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fused_cosine_similarity;
+
+    #[test]
+    fn test_fused_cosine_similarity_matches_naive() {
+        fn naive(a: &[f64], b: &[f64]) -> f64 {
+            let dot: f64 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+            let magnitude1 = a.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
+            let magnitude2 = b.iter().map(|x| x.powi(2)).sum::<f64>().sqrt();
+            dot / (magnitude1 * magnitude2)
+        }
+
+        // Cover lengths that are not a multiple of the number of lanes
+        for ndims in [1, 3, 4, 7, 384, 1536, 1537] {
+            let a: Vec<f64> = (0..ndims).map(|i| ((i * 7) % 13) as f64 - 6.0).collect();
+            let b: Vec<f64> = (0..ndims)
+                .map(|i| ((i * 5) % 11) as f64 * 0.25 + 1.0)
+                .collect();
+
+            let expected = naive(&a, &b);
+            let actual = fused_cosine_similarity(&a, &b);
+
+            assert!(
+                (expected - actual).abs() < 1e-12,
+                "ndims={ndims}: expected {expected}, got {actual}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fused_cosine_similarity_zero_vector() {
+        assert_eq!(
+            fused_cosine_similarity(&[0.0, 0.0, 0.0], &[1.0, 2.0, 3.0]),
+            0.0
+        );
+    }
+}
@@ -1,10 +1,12 @@
-use futures::future::BoxFuture;
+use futures::{future::BoxFuture, stream, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use serde_json::Value;
 
 use crate::embeddings::EmbeddingError;
 
 pub mod in_memory_store;
+pub mod math;
+pub mod tiered_store;
 
 #[derive(Debug, thiserror::Error)]
 pub enum VectorStoreError {
@@ -20,8 +22,90 @@ pub enum VectorStoreError {
 
     #[error("Missing Id: {0}")]
     MissingIdError(String),
+
+    /// Ids appearing more than once in a batch of documents to insert
+    #[error("Duplicate ids in batch: {}", .0.join(", "))]
+    DuplicateIdsError(Vec<String>),
+
+    /// Operation not supported by the vector store
+    #[error("Unsupported operation: {0}")]
+    UnsupportedError(String),
+}
+
+/// Check that a batch of documents to be inserted in a vector store does not contain
+/// the same id more than once. Returns a [VectorStoreError::DuplicateIdsError] listing
+/// the colliding ids (in order of first collision) otherwise.
+///
+/// Vector store integrations with a notion of document id should call this before writing a batch.
+pub fn check_duplicate_ids<I: AsRef<str>>(
+    ids: impl IntoIterator<Item = I>,
+) -> Result<(), VectorStoreError> {
+    let mut seen = std::collections::HashSet::new();
+    let mut duplicates = vec![];
+
+    for id in ids {
+        let id = id.as_ref().to_string();
+        if !seen.insert(id.clone()) && !duplicates.contains(&id) {
+            duplicates.push(id);
+        }
+    }
+
+    if duplicates.is_empty() {
+        Ok(())
+    } else {
+        Err(VectorStoreError::DuplicateIdsError(duplicates))
+    }
+}
+
+/// Score of a search result, tagged with its direction: similarities (e.g.: cosine similarity)
+/// are better when higher, while distances (e.g.: euclidean distance) are better when lower.
+///
+/// Derefs to the raw score, so that it can be used wherever an `f64` is expected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Score {
+    Similarity(f64),
+    Distance(f64),
+}
+
+impl Score {
+    /// The raw score, regardless of its direction.
+    pub fn value(&self) -> f64 {
+        match self {
+            Score::Similarity(value) | Score::Distance(value) => *value,
+        }
+    }
+
+    /// Whether this score ranks strictly before `other`.
+    /// Scores of different kinds are not comparable, in which case this returns `false`.
+    pub fn is_better_than(&self, other: &Score) -> bool {
+        match (self, other) {
+            (Score::Similarity(a), Score::Similarity(b)) => a > b,
+            (Score::Distance(a), Score::Distance(b)) => a < b,
+            _ => false,
+        }
+    }
+}
+
+impl std::ops::Deref for Score {
+    type Target = f64;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Score::Similarity(value) | Score::Distance(value) => value,
+        }
+    }
+}
+
+impl From<Score> for f64 {
+    fn from(score: Score) -> Self {
+        score.value()
+    }
 }
 
+/// Maximum number of queries searched concurrently by the default implementation of
+/// [VectorStoreIndex::top_n_multi].
+pub const TOP_N_MULTI_CONCURRENCY: usize = 8;
+
 /// Trait for vector store indexes
 pub trait VectorStoreIndex: Send + Sync {
     /// Get the top n documents based on the distance to the given query.
@@ -38,8 +122,123 @@ pub trait VectorStoreIndex: Send + Sync {
         query: &str,
         n: usize,
     ) -> impl std::future::Future<Output = Result<Vec<(f64, String)>, VectorStoreError>> + Send;
+
+    /// Same as `top_n` but documents that cannot be deserialized into `T` do not cause the
+    /// whole query to fail (or to be silently dropped, depending on the vector store).
+    /// Returns the successfully deserialized results along with the ids of the documents
+    /// that failed to deserialize and the corresponding errors.
+    /// This is useful to debug schema mismatches between stored documents and `T`.
+    fn top_n_lenient<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> impl std::future::Future<Output = Result<LenientTopNResults<T>, VectorStoreError>> + Send
+    {
+        async move {
+            let mut hits = vec![];
+            let mut errors = vec![];
+
+            for (score, id, doc) in self.top_n::<Value>(query, n).await? {
+                match serde_json::from_value::<T>(doc) {
+                    Ok(doc) => hits.push((score, id, doc)),
+                    Err(e) => errors.push((id, e)),
+                }
+            }
+
+            Ok((hits, errors))
+        }
+    }
+
+    /// Get the top n documents for each of the given queries, e.g.: for query expansion.
+    /// The results are returned in the same order as the queries.
+    ///
+    /// The default implementation runs [VectorStoreIndex::top_n] for each query, with up to
+    /// [TOP_N_MULTI_CONCURRENCY] queries in flight. Vector stores that can batch the embedding
+    /// of the queries and the searches override it.
+    #[allow(clippy::type_complexity)]
+    fn top_n_multi<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        queries: &[&str],
+        n: usize,
+    ) -> impl std::future::Future<Output = Result<Vec<Vec<(f64, String, T)>>, VectorStoreError>> + Send
+    {
+        async move {
+            // Own the queries: borrowing them in the stream closure trips up the `Send` check of the future
+            let queries = queries
+                .iter()
+                .map(|query| query.to_string())
+                .enumerate()
+                .collect::<Vec<_>>();
+
+            let mut results = stream::iter(queries)
+                .map(|(i, query)| async move {
+                    Ok::<_, VectorStoreError>((i, self.top_n(&query, n).await?))
+                })
+                .buffer_unordered(TOP_N_MULTI_CONCURRENCY)
+                .try_collect::<Vec<_>>()
+                .await?;
+
+            results.sort_by_key(|(i, _)| *i);
+            Ok(results.into_iter().map(|(_, result)| result).collect())
+        }
+    }
+
+    /// Tag a raw score returned by [VectorStoreIndex::top_n] or [VectorStoreIndex::top_n_ids]
+    /// with its direction. Scores are similarities by default, vector stores returning
+    /// distances override this.
+    fn score(&self, value: f64) -> Score {
+        Score::Similarity(value)
+    }
+
+    /// Same as `top_n` but the scores are tagged with their direction (see [Score]).
+    fn top_n_scored<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> impl std::future::Future<Output = Result<Vec<(Score, String, T)>, VectorStoreError>> + Send
+    {
+        async move {
+            Ok(self
+                .top_n(query, n)
+                .await?
+                .into_iter()
+                .map(|(score, id, doc)| (self.score(score), id, doc))
+                .collect())
+        }
+    }
+
+    /// Get the number of documents in the vector store.
+    /// Returns a [VectorStoreError::UnsupportedError] by default, for vector stores
+    /// where counting documents is not supported or not cheap.
+    fn count(&self) -> impl std::future::Future<Output = Result<u64, VectorStoreError>> + Send {
+        async {
+            Err(VectorStoreError::UnsupportedError(
+                "count is not supported by this vector store".to_string(),
+            ))
+        }
+    }
+
+    /// Get the document with the given id, e.g.: to retrieve a document cited by the model.
+    /// Returns `None` if there is no document with that id.
+    /// Returns a [VectorStoreError::UnsupportedError] by default, for vector stores
+    /// where documents cannot be looked up by id.
+    fn get_by_id<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        id: &str,
+    ) -> impl std::future::Future<Output = Result<Option<T>, VectorStoreError>> + Send {
+        let _ = id;
+        async {
+            Err(VectorStoreError::UnsupportedError(
+                "get_by_id is not supported by this vector store".to_string(),
+            ))
+        }
+    }
 }
 
+/// Results of [VectorStoreIndex::top_n_lenient]: the successfully deserialized results
+/// and the `(id, error)` pairs of the documents that failed to deserialize.
+pub type LenientTopNResults<T> = (Vec<(f64, String, T)>, Vec<(String, serde_json::Error)>);
+
 pub type TopNResults = Result<Vec<(f64, String, Value)>, VectorStoreError>;
 
 pub trait VectorStoreIndexDyn: Send + Sync {
@@ -99,3 +298,227 @@ fn prune_document(document: serde_json::Value) -> Option<serde_json::Value> {
         Value::Null => Some(Value::Null),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::{check_duplicate_ids, Score, VectorStoreError, VectorStoreIndex};
+    use crate::{
+        embeddings::Embedding,
+        test_utils::MockEmbeddingModel,
+        vector_store::{in_memory_store::InMemoryVectorStore, tiered_store::TieredVectorStore},
+        OneOrMany,
+    };
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Word {
+        word: String,
+        definition: String,
+    }
+
+    #[tokio::test]
+    async fn test_top_n_lenient() {
+        let embedding = |vec| {
+            OneOrMany::one(Embedding {
+                document: "".to_string(),
+                vec,
+            })
+        };
+
+        let index = InMemoryVectorStore::from_documents_with_ids(vec![
+            (
+                "doc0",
+                json!({"word": "flurbo", "definition": "A green alien"}),
+                embedding(vec![1.0, 0.0]),
+            ),
+            (
+                "doc1",
+                // Incompatible document: missing `definition` field
+                json!({"word": "glarb-glarb"}),
+                embedding(vec![1.0, 0.1]),
+            ),
+        ])
+        .index(MockEmbeddingModel::default());
+
+        // Strict deserialization fails for the whole query
+        assert!(index.top_n::<Word>("query", 2).await.is_err());
+
+        let (hits, errors) = index.top_n_lenient::<Word>("query", 2).await.unwrap();
+
+        assert_eq!(
+            hits.into_iter()
+                .map(|(_, id, doc)| (id, doc))
+                .collect::<Vec<_>>(),
+            vec![(
+                "doc0".to_string(),
+                Word {
+                    word: "flurbo".to_string(),
+                    definition: "A green alien".to_string()
+                }
+            )]
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].0, "doc1");
+        assert!(errors[0]
+            .1
+            .to_string()
+            .contains("missing field `definition`"));
+    }
+
+    #[tokio::test]
+    async fn test_count() {
+        let store = || {
+            InMemoryVectorStore::from_documents_with_ids(vec![
+                (
+                    "doc0",
+                    "flurbo".to_string(),
+                    OneOrMany::one(Embedding::default()),
+                ),
+                (
+                    "doc1",
+                    "glarb".to_string(),
+                    OneOrMany::one(Embedding::default()),
+                ),
+            ])
+        };
+
+        assert_eq!(
+            store()
+                .index(MockEmbeddingModel::default())
+                .count()
+                .await
+                .unwrap(),
+            2
+        );
+
+        // Vector stores that do not implement `count` return an error
+        let tiered = TieredVectorStore::new(
+            store().index(MockEmbeddingModel::default()),
+            store().index(MockEmbeddingModel::default()),
+        );
+        assert!(matches!(
+            tiered.count().await,
+            Err(VectorStoreError::UnsupportedError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_by_id() {
+        let store = || {
+            InMemoryVectorStore::from_documents_with_ids(vec![(
+                "doc0",
+                json!({"word": "flurbo", "definition": "A green alien"}),
+                OneOrMany::one(Embedding::default()),
+            )])
+        };
+
+        let index = store().index(MockEmbeddingModel::default());
+        assert_eq!(
+            index.get_by_id::<Word>("doc0").await.unwrap(),
+            Some(Word {
+                word: "flurbo".to_string(),
+                definition: "A green alien".to_string()
+            })
+        );
+        assert_eq!(index.get_by_id::<Word>("doc1").await.unwrap(), None);
+
+        // Vector stores that do not implement `get_by_id` return an error
+        let tiered = TieredVectorStore::new(
+            store().index(MockEmbeddingModel::default()),
+            store().index(MockEmbeddingModel::default()),
+        );
+        assert!(matches!(
+            tiered.get_by_id::<Word>("doc0").await,
+            Err(VectorStoreError::UnsupportedError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_top_n_multi() {
+        let store = || {
+            InMemoryVectorStore::from_documents_with_ids(vec![
+                (
+                    "doc0",
+                    "flurbo".to_string(),
+                    OneOrMany::one(Embedding {
+                        document: "flurbo".to_string(),
+                        vec: vec![1.0, 0.0],
+                    }),
+                ),
+                (
+                    "doc1",
+                    "glarb".to_string(),
+                    OneOrMany::one(Embedding {
+                        document: "glarb".to_string(),
+                        vec: vec![0.0, 1.0],
+                    }),
+                ),
+            ])
+        };
+
+        // Default implementation
+        let tiered = TieredVectorStore::new(
+            store().index(MockEmbeddingModel::default()),
+            store().index(MockEmbeddingModel::default()),
+        );
+        let results = tiered
+            .top_n_multi::<String>(&["query0", "query1", "query2"], 1)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(results
+            .iter()
+            .all(|result| result.len() == 1 && result[0].1 == "doc0"));
+    }
+
+    #[test]
+    fn test_score() {
+        assert!(Score::Similarity(0.9).is_better_than(&Score::Similarity(0.5)));
+        assert!(!Score::Similarity(0.5).is_better_than(&Score::Similarity(0.9)));
+        assert!(Score::Distance(0.5).is_better_than(&Score::Distance(0.9)));
+        assert!(!Score::Distance(0.9).is_better_than(&Score::Distance(0.5)));
+
+        // Scores of different kinds are not comparable
+        assert!(!Score::Similarity(0.9).is_better_than(&Score::Distance(0.5)));
+        assert!(!Score::Distance(0.5).is_better_than(&Score::Similarity(0.9)));
+
+        assert_eq!(*Score::Distance(0.5), 0.5);
+        assert_eq!(f64::from(Score::Similarity(0.9)), 0.9);
+    }
+
+    #[tokio::test]
+    async fn test_top_n_scored() {
+        let index = InMemoryVectorStore::from_documents_with_ids(vec![(
+            "doc0",
+            "flurbo".to_string(),
+            OneOrMany::one(Embedding {
+                document: "flurbo".to_string(),
+                vec: vec![1.0, 0.0],
+            }),
+        )])
+        .index(MockEmbeddingModel::default());
+
+        let results = index.top_n_scored::<String>("flurbo", 1).await.unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].0, Score::Similarity(_)));
+        assert_eq!(results[0].1, "doc0");
+    }
+
+    #[test]
+    fn test_check_duplicate_ids() {
+        assert!(check_duplicate_ids(["doc0", "doc1", "doc2"]).is_ok());
+        assert!(check_duplicate_ids(Vec::<String>::new()).is_ok());
+
+        let err =
+            check_duplicate_ids(["doc0", "doc1", "doc0", "doc2", "doc1", "doc0"]).unwrap_err();
+        assert_eq!(err.to_string(), "Duplicate ids in batch: doc0, doc1");
+        assert!(matches!(
+            err,
+            VectorStoreError::DuplicateIdsError(ids) if ids == vec!["doc0", "doc1"]
+        ));
+    }
+}
@@ -0,0 +1,398 @@
+//! Vector store index combining a "hot" and a "cold" vector store index.
+use std::{cmp::Ordering, collections::HashSet};
+
+use serde::Deserialize;
+
+use super::{Score, VectorStoreError, VectorStoreIndex};
+
+/// [TieredVectorStore] queries two vector store indexes concurrently and merges their results.
+/// This is typically used to keep recent or frequently accessed documents in a fast (but expensive)
+/// "hot" store while archiving the rest in a slower (but cheaper) "cold" store.
+///
+/// If a document id is returned by both stores, the result of the hot store is kept.
+///
+/// By default, the scores of both stores are converted to similarities on the same scale before
+/// merging, so stores returning distances rather than similarities (see [VectorStoreIndex::score])
+/// can be combined: similarities are kept as is, and distances `d` are mapped to `1 / (1 + d)`.
+/// The returned scores are these similarities. The scores are not rescaled per store, so a weak
+/// match of one store still ranks below a strong match of the other.
+///
+/// If both stores use the same embedding model and distance function, use
+/// [TieredVectorStore::raw_scores] to merge on the raw scores instead.
+///
+/// # Example
+/// ```rust
+/// use rig::vector_store::tiered_store::TieredVectorStore;
+///
+/// let index = TieredVectorStore::new(qdrant_index, archive_index);
+///
+/// let results = index.top_n::<Document>("What is a linglingdong?", 5).await?;
+/// ```
+pub struct TieredVectorStore<H: VectorStoreIndex, C: VectorStoreIndex> {
+    hot: H,
+    cold: C,
+    raw_scores: bool,
+}
+
+impl<H: VectorStoreIndex, C: VectorStoreIndex> TieredVectorStore<H, C> {
+    pub fn new(hot: H, cold: C) -> Self {
+        Self {
+            hot,
+            cold,
+            raw_scores: false,
+        }
+    }
+
+    /// Merge the results on the raw scores returned by each store, without converting them.
+    /// The returned scores are the raw scores.
+    ///
+    /// Returns a [VectorStoreError::UnsupportedError] if one store returns similarities and the other distances.
+    pub fn raw_scores(mut self) -> Result<Self, VectorStoreError> {
+        if std::mem::discriminant(&self.hot.score(0.0))
+            != std::mem::discriminant(&self.cold.score(0.0))
+        {
+            return Err(VectorStoreError::UnsupportedError(
+                "Raw scores cannot be merged when one store returns similarities and the other distances"
+                    .into(),
+            ));
+        }
+        self.raw_scores = true;
+        Ok(self)
+    }
+
+    /// Get a reference to the hot vector store index
+    pub fn hot(&self) -> &H {
+        &self.hot
+    }
+
+    /// Get a reference to the cold vector store index
+    pub fn cold(&self) -> &C {
+        &self.cold
+    }
+
+    /// Merge the results of the hot and cold stores, keeping the `n` best results.
+    fn merge<T>(
+        &self,
+        hot: Vec<(f64, String, T)>,
+        cold: Vec<(f64, String, T)>,
+        n: usize,
+    ) -> Vec<(f64, String, T)> {
+        let tag = |results: Vec<(f64, String, T)>, score: &dyn Fn(f64) -> Score| {
+            results
+                .into_iter()
+                .map(|(value, id, doc)| (score(value), id, doc))
+                .collect::<Vec<_>>()
+        };
+        let (hot, cold) = if self.raw_scores {
+            (
+                tag(hot, &|value| self.hot.score(value)),
+                tag(cold, &|value| self.cold.score(value)),
+            )
+        } else {
+            (
+                tag(hot, &|value| similarity(self.hot.score(value))),
+                tag(cold, &|value| similarity(self.cold.score(value))),
+            )
+        };
+
+        let hot_ids = hot
+            .iter()
+            .map(|(_, id, _)| id.clone())
+            .collect::<HashSet<_>>();
+
+        let mut results = hot
+            .into_iter()
+            .chain(cold.into_iter().filter(|(_, id, _)| !hot_ids.contains(id)))
+            .collect::<Vec<_>>();
+
+        // Stable sort so that hot results come first in case of a tie
+        results.sort_by(|(a, _, _), (b, _, _)| {
+            if a.is_better_than(b) {
+                Ordering::Less
+            } else if b.is_better_than(a) {
+                Ordering::Greater
+            } else {
+                Ordering::Equal
+            }
+        });
+        results.truncate(n);
+        results
+            .into_iter()
+            .map(|(score, id, doc)| (score.value(), id, doc))
+            .collect()
+    }
+}
+
+/// Convert a score to a similarity on the scale shared by both stores: similarities are kept as is,
+/// and distances `d` are mapped to `1 / (1 + d)`, so that a distance of `0` is a similarity of `1`.
+fn similarity(score: Score) -> Score {
+    match score {
+        Score::Similarity(value) => Score::Similarity(value),
+        Score::Distance(value) => Score::Similarity(1.0 / (1.0 + value)),
+    }
+}
+
+impl<H: VectorStoreIndex, C: VectorStoreIndex> VectorStoreIndex for TieredVectorStore<H, C> {
+    async fn top_n<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
+        let (hot, cold) = futures::try_join!(
+            self.hot.top_n::<T>(query, n),
+            self.cold.top_n::<T>(query, n)
+        )?;
+
+        Ok(self.merge(hot, cold, n))
+    }
+
+    async fn top_n_ids(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> Result<Vec<(f64, String)>, VectorStoreError> {
+        let (hot, cold) =
+            futures::try_join!(self.hot.top_n_ids(query, n), self.cold.top_n_ids(query, n))?;
+
+        let with_unit = |results: Vec<(f64, String)>| {
+            results
+                .into_iter()
+                .map(|(score, id)| (score, id, ()))
+                .collect::<Vec<_>>()
+        };
+
+        Ok(self
+            .merge(with_unit(hot), with_unit(cold), n)
+            .into_iter()
+            .map(|(score, id, _)| (score, id))
+            .collect())
+    }
+
+    /// Converted scores are similarities. Raw scores have the kind of the scores of both stores.
+    fn score(&self, value: f64) -> Score {
+        if self.raw_scores {
+            self.hot.score(value)
+        } else {
+            Score::Similarity(value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::{
+        embeddings::Embedding,
+        test_utils::MockEmbeddingModel,
+        vector_store::{
+            in_memory_store::{InMemoryVectorIndex, InMemoryVectorStore},
+            Score, VectorStoreError, VectorStoreIndex,
+        },
+        OneOrMany,
+    };
+
+    use super::TieredVectorStore;
+
+    fn index(documents: Vec<(&str, Vec<f64>)>) -> InMemoryVectorIndex<MockEmbeddingModel, String> {
+        InMemoryVectorStore::from_documents_with_ids(documents.into_iter().map(|(id, vec)| {
+            (
+                id,
+                id.to_string(),
+                OneOrMany::one(Embedding {
+                    document: id.to_string(),
+                    vec,
+                }),
+            )
+        }))
+        .index(MockEmbeddingModel::default())
+    }
+
+    /// Index returning the cosine distance (`1 - similarity`) of the results of an in-memory index
+    struct DistanceIndex(InMemoryVectorIndex<MockEmbeddingModel, String>);
+
+    impl VectorStoreIndex for DistanceIndex {
+        async fn top_n<T: for<'a> Deserialize<'a> + Send>(
+            &self,
+            query: &str,
+            n: usize,
+        ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
+            Ok(self
+                .0
+                .top_n(query, n)
+                .await?
+                .into_iter()
+                .map(|(score, id, doc)| (1.0 - score, id, doc))
+                .collect())
+        }
+
+        async fn top_n_ids(
+            &self,
+            query: &str,
+            n: usize,
+        ) -> Result<Vec<(f64, String)>, VectorStoreError> {
+            Ok(self
+                .0
+                .top_n_ids(query, n)
+                .await?
+                .into_iter()
+                .map(|(score, id)| (1.0 - score, id))
+                .collect())
+        }
+
+        fn score(&self, value: f64) -> Score {
+            Score::Distance(value)
+        }
+    }
+
+    fn rounded(results: Vec<(f64, String)>) -> Vec<(String, f64)> {
+        results
+            .into_iter()
+            .map(|(score, id)| (id, (score * 1000.0).round() / 1000.0))
+            .collect()
+    }
+
+    fn ranking(results: &[(&str, f64)]) -> Vec<(String, f64)> {
+        results
+            .iter()
+            .map(|(id, score)| (id.to_string(), *score))
+            .collect()
+    }
+
+    fn tiers() -> (
+        InMemoryVectorIndex<MockEmbeddingModel, String>,
+        InMemoryVectorIndex<MockEmbeddingModel, String>,
+    ) {
+        let hot = index(vec![
+            ("hot_best", vec![1.0, 0.1]),
+            ("hot_worst", vec![0.1, 1.0]),
+            ("shared", vec![1.0, 1.0]),
+        ]);
+        let cold = index(vec![
+            ("cold_best", vec![1.0, 0.0]),
+            ("cold_mid", vec![1.0, 0.5]),
+            ("shared", vec![1.0, 0.0]),
+        ]);
+        (hot, cold)
+    }
+
+    #[tokio::test]
+    async fn test_merged_ranking() {
+        let (hot, cold) = tiers();
+        let index = TieredVectorStore::new(hot, cold).raw_scores().unwrap();
+
+        let results = index.top_n::<String>("query", 4).await.unwrap();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|(_, id, doc)| (id.as_str(), doc.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("cold_best", "cold_best"),
+                ("hot_best", "hot_best"),
+                ("cold_mid", "cold_mid"),
+                ("shared", "shared"),
+            ]
+        );
+
+        // The hot result is kept for documents present in both stores
+        assert_eq!(results[3].0, 1.0 / 2.0_f64.sqrt());
+
+        let ids = index.top_n_ids("query", 4).await.unwrap();
+        assert_eq!(
+            ids.iter().map(|(_, id)| id.as_str()).collect::<Vec<_>>(),
+            vec!["cold_best", "hot_best", "cold_mid", "shared"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_similarity_ranking() {
+        let (hot, cold) = tiers();
+        let index = TieredVectorStore::new(hot, cold);
+
+        // Similarities are merged as is
+        assert_eq!(
+            rounded(index.top_n_ids("query", 6).await.unwrap()),
+            ranking(&[
+                ("cold_best", 1.0),
+                ("hot_best", 0.995),
+                ("cold_mid", 0.894),
+                ("shared", 0.707),
+                ("hot_worst", 0.1),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_distance_ranking() {
+        let (hot, cold) = tiers();
+        let index = TieredVectorStore::new(DistanceIndex(hot), DistanceIndex(cold));
+
+        // Distances are converted to similarities, so the ranking is the same as with similarities
+        assert_eq!(
+            rounded(index.top_n_ids("query", 6).await.unwrap()),
+            ranking(&[
+                ("cold_best", 1.0),
+                ("hot_best", 0.995),
+                ("cold_mid", 0.905),
+                ("shared", 0.773),
+                ("hot_worst", 0.526),
+            ])
+        );
+        assert_eq!(index.score(1.0), Score::Similarity(1.0));
+
+        // Raw distances are merged from the closest to the farthest
+        let index = index.raw_scores().unwrap();
+        let results = index.top_n_ids("query", 4).await.unwrap();
+        assert_eq!(
+            results
+                .iter()
+                .map(|(_, id)| id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["cold_best", "hot_best", "cold_mid", "shared"]
+        );
+        assert!(results.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+        assert_eq!(index.score(1.0), Score::Distance(1.0));
+    }
+
+    #[tokio::test]
+    async fn test_mixed_ranking() {
+        let (hot, cold) = tiers();
+        let index = TieredVectorStore::new(hot, DistanceIndex(cold));
+
+        assert_eq!(
+            rounded(index.top_n_ids("query", 6).await.unwrap()),
+            ranking(&[
+                ("cold_best", 1.0),
+                ("hot_best", 0.995),
+                ("cold_mid", 0.905),
+                ("shared", 0.707),
+                ("hot_worst", 0.1),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_weak_cold_match_ranks_below_strong_hot_match() {
+        let hot = index(vec![("strong", vec![1.0, 0.0]), ("mid", vec![1.0, 1.0])]);
+        // The only (and thus best) result of the cold store is a poor match
+        let cold = index(vec![("poor", vec![0.1, 1.0])]);
+
+        let index = TieredVectorStore::new(hot, DistanceIndex(cold));
+
+        assert_eq!(
+            rounded(index.top_n_ids("query", 3).await.unwrap()),
+            ranking(&[("strong", 1.0), ("mid", 0.707), ("poor", 0.526)])
+        );
+    }
+
+    #[test]
+    fn test_mixed_raw_scores() {
+        let (hot, cold) = tiers();
+        assert!(matches!(
+            TieredVectorStore::new(hot, DistanceIndex(cold)).raw_scores(),
+            Err(VectorStoreError::UnsupportedError(_))
+        ));
+    }
+}
@@ -215,3 +215,88 @@ fn test_multiple_embed_tags() {
         ]
     );
 }
+
+#[test]
+fn test_embed_option() {
+    #[derive(Embed)]
+    struct Movie {
+        #[allow(dead_code)]
+        id: String,
+        #[embed]
+        title: String,
+        #[embed]
+        tagline: Option<String>,
+        #[embed]
+        synopsis: Option<String>,
+    }
+
+    let movie = Movie {
+        id: "doc1".to_string(),
+        title: "Alien".to_string(),
+        tagline: Some("In space no one can hear you scream.".to_string()),
+        synopsis: None,
+    };
+
+    assert_eq!(
+        embeddings::to_texts(movie).unwrap(),
+        vec![
+            "Alien".to_string(),
+            "In space no one can hear you scream.".to_string()
+        ]
+    );
+}
+
+#[test]
+fn test_embed_vec_nested() {
+    #[derive(Embed)]
+    struct Section {
+        #[embed]
+        heading: String,
+        #[embed]
+        body: String,
+    }
+
+    #[derive(Embed)]
+    struct Article {
+        #[embed]
+        title: String,
+        #[embed]
+        sections: Vec<Section>,
+    }
+
+    let article = Article {
+        title: "Flurbos".to_string(),
+        sections: vec![
+            Section {
+                heading: "Origins".to_string(),
+                body: "Flurbos were first seen on Jiro.".to_string(),
+            },
+            Section {
+                heading: "Uses".to_string(),
+                body: "Flurbos are used to farm the land.".to_string(),
+            },
+        ],
+    };
+
+    assert_eq!(
+        embeddings::to_texts(article).unwrap(),
+        vec![
+            "Flurbos".to_string(),
+            "Origins".to_string(),
+            "Flurbos were first seen on Jiro.".to_string(),
+            "Uses".to_string(),
+            "Flurbos are used to farm the land.".to_string()
+        ]
+    );
+
+    // Empty vectors contribute nothing
+    let article = Article {
+        title: "Glarbs".to_string(),
+        sections: vec![],
+    };
+
+    assert_eq!(
+        embeddings::to_texts(article).unwrap(),
+        vec!["Glarbs".to_string()]
+    );
+}
@@ -1,10 +1,13 @@
+use std::sync::Arc;
+
 use lancedb::{
+    arrow::arrow_schema::{DataType, Field, Schema},
     query::{QueryBase, VectorQuery},
     DistanceType,
 };
 use rig::{
     embeddings::embedding::EmbeddingModel,
-    vector_store::{VectorStoreError, VectorStoreIndex},
+    vector_store::{Score, VectorStoreError, VectorStoreIndex},
 };
 use serde::Deserialize;
 use serde_json::Value;
@@ -20,6 +23,90 @@ fn serde_to_rig_error(e: serde_json::Error) -> VectorStoreError {
     VectorStoreError::JsonError(e)
 }
 
+/// Name of the id column of the tables created by [create_table_for].
+pub const ID_COLUMN: &str = "id";
+/// Name of the column holding the JSON serialized documents in the tables created by [create_table_for].
+pub const DOCUMENT_COLUMN: &str = "document";
+/// Name of the embeddings column of the tables created by [create_table_for].
+pub const VECTOR_COLUMN: &str = "vector";
+
+/// Arrow schema of the tables created by [create_table_for], for embeddings of `ndims` dimensions.
+///
+/// - `id` (`Utf8`): id of the document
+/// - `document` (`Utf8`): JSON serialized document
+/// - `vector` (`FixedSizeList<Float32, ndims>`): embedding of the document
+pub fn table_schema(ndims: usize) -> Schema {
+    Schema::new(vec![
+        Field::new(ID_COLUMN, DataType::Utf8, false),
+        Field::new(DOCUMENT_COLUMN, DataType::Utf8, false),
+        Field::new(
+            VECTOR_COLUMN,
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                ndims as i32,
+            ),
+            false,
+        ),
+    ])
+}
+
+/// Get the table `name` to store documents embedded with `model`,
+/// creating it (empty) with the schema returned by [table_schema] if it does not exist yet.
+/// The number of dimensions of the embeddings is given by [EmbeddingModel::ndims].
+///
+/// Returns an error if the table already exists with a different schema, e.g.: if it
+/// was created for another embedding model.
+///
+/// Since documents are stored as JSON, search results deserialize as `{"id": ..., "document": ...}`,
+/// where `document` is the JSON serialized document.
+///
+/// # Example
+/// ```ignore
+/// let db = lancedb::connect("data/lancedb-store").execute().await?;
+/// let table = rig_lancedb::create_table_for(&db, "words", &model).await?;
+/// let index = LanceDbVectorIndex::new(table, model, rig_lancedb::ID_COLUMN, SearchParams::default()).await?;
+/// ```
+pub async fn create_table_for<M: EmbeddingModel>(
+    db: &lancedb::Connection,
+    name: &str,
+    model: &M,
+) -> Result<lancedb::Table, VectorStoreError> {
+    let schema = Arc::new(table_schema(model.ndims()));
+
+    let table_names = db
+        .table_names()
+        .execute()
+        .await
+        .map_err(lancedb_to_rig_error)?;
+
+    if !table_names.iter().any(|table_name| table_name == name) {
+        return db
+            .create_empty_table(name, schema)
+            .execute()
+            .await
+            .map_err(lancedb_to_rig_error);
+    }
+
+    let table = db
+        .open_table(name)
+        .execute()
+        .await
+        .map_err(lancedb_to_rig_error)?;
+
+    if table.schema().await.map_err(lancedb_to_rig_error)?.fields() != schema.fields() {
+        return Err(VectorStoreError::DatastoreError(
+            format!(
+                "Table `{}` already exists with a different schema than expected for {}-dimensional embeddings",
+                name,
+                model.ndims()
+            )
+            .into(),
+        ));
+    }
+
+    Ok(table)
+}
+
 /// Type on which vector searches can be performed for a lanceDb table.
 /// # Example
 /// ```
@@ -285,4 +372,9 @@ impl<M: EmbeddingModel + Sync + Send> VectorStoreIndex for LanceDbVectorIndex<M>
             })
             .collect()
     }
+
+    /// LanceDB returns the distance of each result to the query.
+    fn score(&self, value: f64) -> Score {
+        Score::Distance(value)
+    }
 }
@@ -92,3 +92,35 @@ async fn vector_search_test() {
 
     db.drop_db().await.unwrap();
 }
+
+#[tokio::test]
+async fn create_table_for_test() {
+    let openai_client = Client::from_env();
+    let model = openai_client.embedding_model(openai::TEXT_EMBEDDING_ADA_002);
+
+    let db = lancedb::connect("data/lancedb-create-table")
+        .execute()
+        .await
+        .unwrap();
+
+    let table = rig_lancedb::create_table_for(&db, "words", &model)
+        .await
+        .unwrap();
+    assert_eq!(
+        table.schema().await.unwrap().fields(),
+        rig_lancedb::table_schema(model.ndims()).fields()
+    );
+
+    // The existing table is returned if the schema matches
+    rig_lancedb::create_table_for(&db, "words", &model)
+        .await
+        .unwrap();
+
+    // Embeddings of another size do not fit in the existing table
+    let other_model = openai_client.embedding_model_with_ndims(openai::TEXT_EMBEDDING_3_SMALL, 512);
+    assert!(rig_lancedb::create_table_for(&db, "words", &other_model)
+        .await
+        .is_err());
+
+    db.drop_db().await.unwrap();
+}
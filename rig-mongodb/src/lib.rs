@@ -109,21 +109,60 @@ impl<M: EmbeddingModel, C: Send + Sync> MongoDbVectorIndex<M, C> {
             filter,
             exact,
             num_candidates,
+            exclude_ids,
+            ..
         } = &self.search_params;
 
+        // Fetch extra results to make up for the excluded documents removed by the exclusion stage
+        let limit = n + exclude_ids.len();
+
         doc! {
           "$vectorSearch": {
             "index": &self.index_name,
             "path": self.embedded_field.clone(),
             "queryVector": &prompt_embedding.vec,
-            "numCandidates": num_candidates.unwrap_or((n * 10) as u32),
-            "limit": n as u32,
+            "numCandidates": num_candidates.unwrap_or((limit * 10) as u32),
+            "limit": limit as u32,
             "filter": filter,
             "exact": exact.unwrap_or(false)
           }
         }
     }
 
+    /// Exclusion stages of aggregation pipeline of mongoDB collection.
+    /// Removes the documents whose `_id` is in the excluded ids of the search params and limits the results to `n`.
+    /// To be used by implementations of top_n and top_n_ids methods on VectorStoreIndex trait for MongoDbVectorIndex.
+    fn pipeline_exclude_stages(&self, n: usize) -> Vec<bson::Document> {
+        if self.search_params.exclude_ids.is_empty() {
+            return vec![];
+        }
+
+        vec![
+            doc! {
+              "$match": {
+                "_id": { "$nin": &self.search_params.exclude_ids }
+              }
+            },
+            doc! {
+              "$limit": n as u32
+            },
+        ]
+    }
+
+    /// Projection stage of aggregation pipeline of mongoDB collection.
+    /// Uses the projection of the search params if set, otherwise excludes the embedded field.
+    /// To be used by implementations of top_n method on VectorStoreIndex trait for MongoDbVectorIndex.
+    fn pipeline_project_stage(&self) -> bson::Document {
+        match &self.search_params.projection {
+            Some(projection) => doc! { "$project": projection },
+            None => doc! {
+              "$project": {
+                self.embedded_field.clone(): 0,
+              },
+            },
+        }
+    }
+
     /// Score declaration stage of aggregation pipeline of mongoDB collection.
     /// /// To be used by implementations of top_n and top_n_ids methods on VectorStoreIndex trait for MongoDbVectorIndex.
     fn pipeline_score_stage(&self) -> bson::Document {
@@ -173,6 +212,82 @@ impl<M: EmbeddingModel, C: Send + Sync> MongoDbVectorIndex<M, C> {
             search_params,
         })
     }
+
+    /// Create a new `MongoDbVectorIndex` without checking that the index exists and is queryable.
+    /// The `embedded_field` (i.e.: the path of the embeddings in the documents) must be given explicitly,
+    /// since it is not read from the index definition.
+    ///
+    /// This is useful right after creating the index with `create_search_index`, since Atlas builds
+    /// indexes asynchronously: documents can be inserted while the index is being built, but searches
+    /// fail until the index is queryable.
+    pub fn new_unchecked(
+        collection: mongodb::Collection<C>,
+        model: M,
+        index_name: &str,
+        embedded_field: &str,
+        search_params: SearchParams,
+    ) -> Self {
+        Self {
+            collection,
+            model,
+            index_name: index_name.to_string(),
+            embedded_field: embedded_field.to_string(),
+            search_params,
+        }
+    }
+}
+
+impl<M: EmbeddingModel, C: Send + Sync> MongoDbVectorIndex<M, C> {
+    /// Update the fields of the document with the given `_id` using a `$set` operation, without
+    /// re-embedding the document. `patch` must be a JSON object and cannot contain the embedded field.
+    pub async fn update_metadata(
+        &self,
+        id: &str,
+        patch: serde_json::Value,
+    ) -> Result<(), VectorStoreError> {
+        let patch =
+            bson::to_document(&patch).map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        if patch.contains_key(&self.embedded_field) {
+            return Err(VectorStoreError::DatastoreError(
+                format!(
+                    "Cannot update embedded field {} without re-embedding the document",
+                    self.embedded_field
+                )
+                .into(),
+            ));
+        }
+
+        let result = self
+            .collection
+            .update_one(doc! { "_id": id }, doc! { "$set": patch })
+            .await
+            .map_err(mongodb_to_rig_error)?;
+
+        if result.matched_count == 0 {
+            return Err(VectorStoreError::MissingIdError(id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Delete the documents with the given `_id`s. Returns the number of deleted documents.
+    /// Ids that do not match any document are ignored.
+    pub async fn delete_by_ids(&self, ids: &[String]) -> Result<u64, VectorStoreError> {
+        self.delete_where(doc! { "_id": { "$in": ids } }).await
+    }
+
+    /// Delete the documents matching the given filter. Returns the number of deleted documents.
+    /// See [MongoDB query filters](https://www.mongodb.com/docs/manual/core/document/#query-filter-documents) for more information.
+    pub async fn delete_where(&self, filter: bson::Document) -> Result<u64, VectorStoreError> {
+        let result = self
+            .collection
+            .delete_many(filter)
+            .await
+            .map_err(mongodb_to_rig_error)?;
+
+        Ok(result.deleted_count)
+    }
 }
 
 /// See [MongoDB Vector Search](`https://www.mongodb.com/docs/atlas/atlas-vector-search/vector-search-stage/`) for more information
@@ -182,6 +297,8 @@ pub struct SearchParams {
     filter: mongodb::bson::Document,
     exact: Option<bool>,
     num_candidates: Option<u32>,
+    exclude_ids: Vec<String>,
+    projection: Option<mongodb::bson::Document>,
 }
 
 impl SearchParams {
@@ -191,9 +308,28 @@ impl SearchParams {
             filter: doc! {},
             exact: None,
             num_candidates: None,
+            exclude_ids: vec![],
+            projection: None,
         }
     }
 
+    /// Sets the projection applied to the documents returned by `top_n`, e.g.: `doc! { "definition": 1, "score": 1 }`
+    /// to only fetch the fields needed by the caller. Replaces the default projection, which excludes the embedded field.
+    /// The `score` and `_id` fields must not be excluded since they are needed to build the results.
+    /// See [MongoDB $project](https://www.mongodb.com/docs/manual/reference/operator/aggregation/project/) for more information.
+    pub fn projection(mut self, projection: mongodb::bson::Document) -> Self {
+        self.projection = Some(projection);
+        self
+    }
+
+    /// Sets the ids of the documents to exclude from the search results, e.g.: to avoid returning
+    /// documents that were already retrieved earlier in a conversation.
+    /// Unlike the pre-filter, this does not require the `_id` field to be indexed as a filter field.
+    pub fn exclude_ids(mut self, exclude_ids: Vec<String>) -> Self {
+        self.exclude_ids = exclude_ids;
+        self
+    }
+
     /// Sets the pre-filter field of the search params.
     /// See [MongoDB vector Search](https://www.mongodb.com/docs/atlas/atlas-vector-search/vector-search-stage/) for more information.
     pub fn filter(mut self, filter: mongodb::bson::Document) -> Self {
@@ -233,17 +369,14 @@ impl<M: EmbeddingModel + Sync + Send, C: Sync + Send> VectorStoreIndex
 
         let mut cursor = self
             .collection
-            .aggregate([
-                self.pipeline_search_stage(&prompt_embedding, n),
-                self.pipeline_score_stage(),
-                {
-                    doc! {
-                        "$project": {
-                            self.embedded_field.clone(): 0,
-                        },
-                    }
-                },
-            ])
+            .aggregate(
+                [
+                    vec![self.pipeline_search_stage(&prompt_embedding, n)],
+                    self.pipeline_exclude_stages(n),
+                    vec![self.pipeline_score_stage(), self.pipeline_project_stage()],
+                ]
+                .concat(),
+            )
             .await
             .map_err(mongodb_to_rig_error)?
             .with_type::<serde_json::Value>();
@@ -278,16 +411,22 @@ impl<M: EmbeddingModel + Sync + Send, C: Sync + Send> VectorStoreIndex
 
         let mut cursor = self
             .collection
-            .aggregate([
-                self.pipeline_search_stage(&prompt_embedding, n),
-                self.pipeline_score_stage(),
-                doc! {
-                    "$project": {
-                        "_id": 1,
-                        "score": 1
-                    },
-                },
-            ])
+            .aggregate(
+                [
+                    vec![self.pipeline_search_stage(&prompt_embedding, n)],
+                    self.pipeline_exclude_stages(n),
+                    vec![
+                        self.pipeline_score_stage(),
+                        doc! {
+                            "$project": {
+                                "_id": 1,
+                                "score": 1
+                            },
+                        },
+                    ],
+                ]
+                .concat(),
+            )
             .await
             .map_err(mongodb_to_rig_error)?
             .with_type::<serde_json::Value>();
@@ -310,4 +449,29 @@ impl<M: EmbeddingModel + Sync + Send, C: Sync + Send> VectorStoreIndex
 
         Ok(results)
     }
+
+    /// Implement the `count` method of the `VectorStoreIndex` trait for `MongoDbVectorIndex`.
+    /// Counts all the documents of the collection, regardless of the pre-filter of the search params.
+    async fn count(&self) -> Result<u64, VectorStoreError> {
+        self.collection
+            .count_documents(doc! {})
+            .await
+            .map_err(mongodb_to_rig_error)
+    }
+
+    /// Get the document with the given `_id`, without its embedded field.
+    async fn get_by_id<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        id: &str,
+    ) -> Result<Option<T>, VectorStoreError> {
+        let doc = self
+            .collection
+            .clone_with_type::<serde_json::Value>()
+            .find_one(doc! { "_id": id })
+            .projection(doc! { self.embedded_field.clone(): 0 })
+            .await
+            .map_err(mongodb_to_rig_error)?;
+
+        Ok(doc.map(serde_json::from_value).transpose()?)
+    }
 }
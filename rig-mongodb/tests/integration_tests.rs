@@ -67,14 +67,83 @@ async fn vector_search_test() {
     // Note: a vector index called "vector_index" must exist on the MongoDB collection you are querying.
     // IMPORTANT: Reuse the same model that was used to generate the embeddings
     let index = MongoDbVectorIndex::new(
-        collection,
-        model,
+        collection.clone(),
+        model.clone(),
         VECTOR_SEARCH_INDEX_NAME,
         SearchParams::new(),
     )
     .await
     .unwrap();
 
+    assert_eq!(index.count().await.unwrap(), 3);
+
+    assert_eq!(
+        index.get_by_id::<serde_json::Value>("doc0").await.unwrap(),
+        Some(json!({
+            "_id": "doc0",
+            "definition": "Definition of a *flurbo*: A flurbo is a green alien that lives on cold planets",
+        }))
+    );
+    assert_eq!(
+        index
+            .get_by_id::<serde_json::Value>("missing")
+            .await
+            .unwrap(),
+        None
+    );
+
+    // An index created without checks behaves the same once the index is queryable
+    let unchecked_index = MongoDbVectorIndex::new_unchecked(
+        collection.clone(),
+        model.clone(),
+        VECTOR_SEARCH_INDEX_NAME,
+        "embedding",
+        SearchParams::new(),
+    );
+    assert_eq!(
+        unchecked_index
+            .top_n_ids("What is a linglingdong?", 3)
+            .await
+            .unwrap(),
+        index.top_n_ids("What is a linglingdong?", 3).await.unwrap()
+    );
+
+    // Excluded ids never appear in the results, even when they would rank first
+    let excluding_index = MongoDbVectorIndex::new(
+        collection.clone(),
+        model.clone(),
+        VECTOR_SEARCH_INDEX_NAME,
+        SearchParams::new().exclude_ids(vec!["doc2".to_string()]),
+    )
+    .await
+    .unwrap();
+
+    let results = excluding_index
+        .top_n_ids("What is a linglingdong?", 2)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|(_, id)| !id.contains("doc2")));
+
+    // Only fetch the fields needed
+    let projected_index = MongoDbVectorIndex::new(
+        collection.clone(),
+        model.clone(),
+        VECTOR_SEARCH_INDEX_NAME,
+        SearchParams::new().projection(doc! { "score": 1 }),
+    )
+    .await
+    .unwrap();
+
+    let results = projected_index
+        .top_n::<serde_json::Value>("What is a linglingdong?", 1)
+        .await
+        .unwrap();
+
+    let (score, _, value) = &results.first().unwrap();
+    assert_eq!(*value, json!({ "_id": "doc2", "score": score }));
+
     // Query the index
     let results = index
         .top_n::<serde_json::Value>("What is a linglingdong?", 1)
@@ -90,7 +159,51 @@ async fn vector_search_test() {
             "definition": "Definition of a *linglingdong*: A term used by inhabitants of the far side of the moon to describe humans.".to_string(),
             "score": score
         })
-    )
+    );
+
+    // Update the document metadata without re-embedding it
+    index
+        .update_metadata("doc2", json!({"status": "archived"}))
+        .await
+        .unwrap();
+
+    // Wait for the update to be indexed
+    sleep(Duration::from_secs(5)).await;
+
+    let results = index
+        .top_n::<serde_json::Value>("What is a linglingdong?", 1)
+        .await
+        .unwrap();
+
+    let (new_score, _, value) = &results.first().unwrap();
+
+    assert_eq!(new_score, score);
+    assert_eq!(
+        *value,
+        json!({
+            "_id": "doc2".to_string(),
+            "definition": "Definition of a *linglingdong*: A term used by inhabitants of the far side of the moon to describe humans.".to_string(),
+            "status": "archived",
+            "score": score
+        })
+    );
+
+    // Delete documents, ignoring ids that do not exist
+    assert_eq!(
+        index
+            .delete_by_ids(&["doc2".to_string(), "missing".to_string()])
+            .await
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        index
+            .delete_where(doc! { "status": "archived" })
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(index.delete_where(doc! {}).await.unwrap(), 2);
 }
 
 async fn create_search_index(collection: &Collection<bson::Document>) {
@@ -102,6 +102,46 @@ fn neo4j_to_rig_error(e: neo4rs::Error) -> VectorStoreError {
     VectorStoreError::DatastoreError(Box::new(e))
 }
 
+/// Quote a label or property name with backticks so it can be formatted into a Cypher query.
+/// Backticks in the name are doubled, so the name cannot end the quoting early.
+fn escape_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// State of a Neo4j index, as reported by `SHOW INDEXES`. See [Neo4jClient::index_status].
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexState {
+    /// The index is ready to be queried.
+    Online,
+    /// The index is being populated in the background, e.g.: right after its creation.
+    Populating { percent: f64 },
+    /// The index could not be populated.
+    Failed { reason: String },
+    /// There is no index with that name.
+    NotFound,
+}
+
+impl IndexState {
+    fn from_show_indexes(
+        state: &str,
+        population_percent: f64,
+        failure_message: Option<String>,
+    ) -> Result<Self, VectorStoreError> {
+        match state {
+            "ONLINE" => Ok(IndexState::Online),
+            "POPULATING" => Ok(IndexState::Populating {
+                percent: population_percent,
+            }),
+            "FAILED" => Ok(IndexState::Failed {
+                reason: failure_message.unwrap_or_default(),
+            }),
+            state => Err(VectorStoreError::DatastoreError(
+                format!("Unknown index state: {}", state).into(),
+            )),
+        }
+    }
+}
+
 pub trait ToBoltType {
     fn to_bolt_type(&self) -> BoltType;
 }
@@ -153,11 +193,18 @@ impl Neo4jClient {
     SHOW VECTOR INDEXES
     YIELD name, properties, options
     WHERE name=$index_name
-    RETURN name, properties, options
+    RETURN name, properties, options, labelsOrTypes
     ";
 
     const SHOW_INDEXES_QUERY: &'static str = "SHOW VECTOR INDEXES YIELD name RETURN name";
 
+    const INDEX_STATUS_QUERY: &'static str = "
+    SHOW INDEXES
+    YIELD name, state, populationPercent, failureMessage
+    WHERE name=$index_name
+    RETURN state, populationPercent, failureMessage
+    ";
+
     pub fn new(graph: Graph) -> Self {
         Self { graph }
     }
@@ -209,6 +256,8 @@ impl Neo4jClient {
             name: String,
             properties: Vec<String>,
             options: IndexOptions,
+            #[serde(rename = "labelsOrTypes", default)]
+            labels_or_types: Vec<String>,
         }
 
         #[derive(Deserialize)]
@@ -240,17 +289,17 @@ impl Neo4jClient {
                     model.ndims()
                 );
             }
-            IndexConfig::new(index.name.clone())
+            let index_config = IndexConfig::new(index.name.clone())
                 .embedding_property(index.properties.first().unwrap())
                 .similarity_function(VectorSimilarityFunction::from_str(
                     &index.options.index_config.vector_similarity_function,
-                )?)
+                )?);
+            match index.labels_or_types.first() {
+                Some(label) => index_config.node_label(label),
+                None => index_config,
+            }
         } else {
-            let indexes = Self::execute_and_collect::<String>(
-                &self.graph,
-                neo4rs::query(Self::SHOW_INDEXES_QUERY),
-            )
-            .await?;
+            let indexes = self.list_vector_indexes().await?;
             return Err(VectorStoreError::DatastoreError(Box::new(
                 std::io::Error::new(
                     std::io::ErrorKind::NotFound,
@@ -269,9 +318,12 @@ impl Neo4jClient {
         ))
     }
 
-    /// Calls the `CREATE VECTOR INDEX` Neo4j query and waits for the index to be created.
+    /// Calls the `CREATE VECTOR INDEX` Neo4j query and waits (up to 10 seconds) for the index to be created.
     /// A newly created index is not immediately fully available but is created (i.e. data is indexed) in the background.
     ///
+    /// Returns the state of the index once the wait is over: if it is still [IndexState::Populating],
+    /// use [Neo4jClient::index_status] to poll until it is [IndexState::Online].
+    ///
     /// ❗ If there is already an index targeting the same node label and property, the new index creation will fail.
     ///
     /// ### Arguments
@@ -285,7 +337,7 @@ impl Neo4jClient {
         index_config: IndexConfig,
         node_label: &str,
         model: &impl EmbeddingModel,
-    ) -> Result<(), VectorStoreError> {
+    ) -> Result<IndexState, VectorStoreError> {
         // Create a vector index on our vector store
         tracing::info!("Creating vector index {} ...", index_config.index_name);
 
@@ -300,7 +352,8 @@ impl Neo4jClient {
                     `vector.similarity_function`: $similarity_function
                 }}
             }}",
-            node_label, index_config.embedding_property
+            escape_identifier(node_label),
+            escape_identifier(&index_config.embedding_property)
         );
 
         self.graph
@@ -332,11 +385,58 @@ impl Neo4jClient {
             );
         }
 
-        tracing::info!(
-            "Index created successfully with name: {}",
-            index_config.index_name
-        );
-        Ok(())
+        let state = self.index_status(&index_config.index_name).await?;
+        if state == IndexState::Online {
+            tracing::info!(
+                "Index created successfully with name: {}",
+                index_config.index_name
+            );
+        }
+        Ok(state)
+    }
+
+    /// Get the state of the index named `index_name`, e.g.: to wait until a newly created index
+    /// is [IndexState::Online].
+    pub async fn index_status(&self, index_name: &str) -> Result<IndexState, VectorStoreError> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct IndexStatus {
+            state: String,
+            population_percent: f64,
+            failure_message: Option<String>,
+        }
+
+        let status = Self::execute_and_collect::<IndexStatus>(
+            &self.graph,
+            neo4rs::query(Self::INDEX_STATUS_QUERY).param("index_name", index_name),
+        )
+        .await?;
+
+        match status.into_iter().next() {
+            Some(status) => IndexState::from_show_indexes(
+                &status.state,
+                status.population_percent,
+                status.failure_message,
+            ),
+            None => Ok(IndexState::NotFound),
+        }
+    }
+
+    /// Calls the `DROP INDEX` Neo4j query. Does nothing if no index named `index_name` exists.
+    /// Unlike index creation, dropping an index takes effect immediately.
+    pub async fn drop_vector_index(&self, index_name: &str) -> Result<(), VectorStoreError> {
+        tracing::info!("Dropping vector index {} ...", index_name);
+
+        self.graph
+            .run(neo4rs::query("DROP INDEX $index_name IF EXISTS").param("index_name", index_name))
+            .await
+            .map_err(neo4j_to_rig_error)
+    }
+
+    /// Returns the names of the vector indexes of the database.
+    pub async fn list_vector_indexes(&self) -> Result<Vec<String>, VectorStoreError> {
+        Self::execute_and_collect::<String>(&self.graph, neo4rs::query(Self::SHOW_INDEXES_QUERY))
+            .await
     }
 }
 
@@ -363,6 +463,36 @@ mod tests {
         plot: String,
     }
 
+    #[test]
+    fn test_index_state() {
+        assert_eq!(
+            IndexState::from_show_indexes("ONLINE", 100.0, None).unwrap(),
+            IndexState::Online
+        );
+        assert_eq!(
+            IndexState::from_show_indexes("POPULATING", 42.5, None).unwrap(),
+            IndexState::Populating { percent: 42.5 }
+        );
+        assert_eq!(
+            IndexState::from_show_indexes("FAILED", 0.0, Some("Out of memory".to_string()))
+                .unwrap(),
+            IndexState::Failed {
+                reason: "Out of memory".to_string()
+            }
+        );
+        assert!(IndexState::from_show_indexes("UNKNOWN", 0.0, None).is_err());
+    }
+
+    #[test]
+    fn test_escape_identifier() {
+        assert_eq!(escape_identifier("Movie"), "`Movie`");
+        assert_eq!(escape_identifier("Sci Fi"), "`Sci Fi`");
+        assert_eq!(
+            escape_identifier("a`) DETACH DELETE n //"),
+            "`a``) DETACH DELETE n //`"
+        );
+    }
+
     #[tokio::test]
     async fn test_connect() {
         let result = Neo4jClient::from_config(
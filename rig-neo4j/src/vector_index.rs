@@ -8,10 +8,11 @@ use neo4rs::{Graph, Query};
 use rig::{
     embeddings::{Embedding, EmbeddingModel},
     vector_store::{VectorStoreError, VectorStoreIndex},
+    OneOrMany,
 };
 use serde::{de::Error, Deserialize, Serialize};
 
-use crate::Neo4jClient;
+use crate::{escape_identifier, neo4j_to_rig_error, Neo4jClient, ToBoltType};
 
 pub struct Neo4jVectorIndex<M: EmbeddingModel> {
     graph: Graph,
@@ -27,11 +28,14 @@ pub struct Neo4jVectorIndex<M: EmbeddingModel> {
 /// - `index_name`: "vector_index"
 /// - `embedding_property`: "embedding"
 /// - `similarity_function`: VectorSimilarityFunction::Cosine
+/// - `node_label`: None
 #[derive(Serialize, Deserialize, Clone)]
 pub struct IndexConfig {
     pub index_name: String,
     pub embedding_property: String,
     pub similarity_function: VectorSimilarityFunction,
+    /// Label of the indexed nodes. Required to insert documents with [Neo4jVectorIndex::insert_documents].
+    pub node_label: Option<String>,
 }
 
 impl Default for IndexConfig {
@@ -40,6 +44,7 @@ impl Default for IndexConfig {
             index_name: "vector_index".to_string(),
             embedding_property: "embedding".to_string(),
             similarity_function: VectorSimilarityFunction::Cosine,
+            node_label: None,
         }
     }
 }
@@ -50,9 +55,15 @@ impl IndexConfig {
             index_name: index_name.into(),
             embedding_property: "embedding".to_string(),
             similarity_function: VectorSimilarityFunction::Cosine,
+            node_label: None,
         }
     }
 
+    pub fn node_label(mut self, node_label: &str) -> Self {
+        self.node_label = Some(node_label.to_string());
+        self
+    }
+
     pub fn index_name(mut self, index_name: &str) -> Self {
         self.index_name = index_name.to_string();
         self
@@ -101,6 +112,9 @@ const BASE_VECTOR_SEARCH_QUERY: &str = "
     YIELD node, score
 ";
 
+/// Maximum number of nodes created per transaction by [Neo4jVectorIndex::insert_documents].
+const INSERT_BATCH_SIZE: usize = 1000;
+
 impl<M: EmbeddingModel> Neo4jVectorIndex<M> {
     pub fn new(
         graph: Graph,
@@ -149,7 +163,7 @@ impl<M: EmbeddingModel> Neo4jVectorIndex<M> {
             if return_node {
                 format!(
                     ", node {{.*, {}:null }} as node",
-                    self.index_config.embedding_property
+                    escape_identifier(&self.index_config.embedding_property)
                 )
             } else {
                 "".to_string()
@@ -163,6 +177,251 @@ impl<M: EmbeddingModel> Neo4jVectorIndex<M> {
             .param("num_candidates", n as i64)
             .param("index_name", self.index_config.index_name.clone())
     }
+
+    /// Get the top n nodes matching the query along with related graph entities, in a single query.
+    ///
+    /// `relation_pattern` is appended to the vector search, after the matched node is bound to `n`.
+    /// It must reference `n` and bind the related entities to a `related` variable, e.g.
+    /// `OPTIONAL MATCH (n)-[:DIRECTED_BY]->(related:Director)`. The properties of the related entities
+    /// are collected and deserialized as `R`.
+    ///
+    /// ❗ The pattern is inserted verbatim in the Cypher query. Never build it from untrusted input.
+    ///
+    /// Query template:
+    /// ```cypher
+    /// CALL db.index.vector.queryNodes($index_name, $num_candidates, $queryVector)
+    /// YIELD node, score
+    /// WHERE {where_clause}
+    /// WITH node AS n, score, ID(node) AS element_id
+    /// {relation_pattern}
+    /// RETURN score, element_id, n {.*, embedding:null } AS node, collect(related {.*}) AS related
+    /// ```
+    pub async fn top_n_with_relations<T, R>(
+        &self,
+        query: &str,
+        n: usize,
+        relation_pattern: &str,
+    ) -> Result<Vec<(f64, String, T, Vec<R>)>, VectorStoreError>
+    where
+        T: for<'a> Deserialize<'a> + Send,
+        R: for<'a> Deserialize<'a> + Send,
+    {
+        if !relation_pattern.contains("(n)") && !relation_pattern.contains("(n:") {
+            return Err(VectorStoreError::DatastoreError(
+                format!(
+                    "Relation pattern must reference the matched node `n`: {}",
+                    relation_pattern
+                )
+                .into(),
+            ));
+        }
+
+        let prompt_embedding = self.embedding_model.embed_text(query).await?;
+
+        let where_clause = match &self.search_params.post_vector_search_filter {
+            Some(filter) => format!("WHERE {}", filter),
+            None => "".to_string(),
+        };
+
+        let query = format!(
+            "{}\t{}\n\tWITH node AS n, score, ID(node) AS element_id\n\t{}\n\tRETURN score, element_id, n {{.*, {}:null }} AS node, collect(related {{.*}}) AS related",
+            BASE_VECTOR_SEARCH_QUERY,
+            where_clause,
+            relation_pattern,
+            escape_identifier(&self.index_config.embedding_property)
+        );
+
+        tracing::debug!("Query before params: {}", query);
+
+        let query = Query::new(query)
+            .param("queryVector", prompt_embedding.vec)
+            .param("num_candidates", n as i64)
+            .param("index_name", self.index_config.index_name.clone());
+
+        let rows = Neo4jClient::execute_and_collect::<RowResultRelations<T, R>>(&self.graph, query)
+            .await?;
+
+        let mut results = rows
+            .into_iter()
+            .map(|row| (row.score, row.element_id.to_string(), row.node, row.related))
+            .collect::<Vec<_>>();
+
+        // Aggregation does not preserve the order of the vector search
+        results.sort_by(|(a, ..), (b, ..)| b.total_cmp(a));
+
+        Ok(results)
+    }
+
+    /// Get the top n nodes matching the query using both the vector index and the full-text index
+    /// `fulltext_index_name`, in a single query. Useful to improve recall on queries with exact terms
+    /// (e.g.: names or identifiers) that embeddings do not capture well.
+    ///
+    /// Each index returns up to `n` candidates, whose scores are normalized by the best score of that index
+    /// (all scores of an index are 0 if its best score is not positive).
+    /// The union of the candidates is reranked by `alpha * vector_score + (1 - alpha) * fulltext_score`,
+    /// a node missing from the results of an index getting a score of 0 for it. An `alpha` of 1 only uses
+    /// the vector scores and an `alpha` of 0 only the full-text scores.
+    ///
+    /// The query text is passed as is to the full-text index, so it is interpreted with the
+    /// [Lucene query syntax](https://lucene.apache.org/core/9_0_0/queryparser/org/apache/lucene/queryparser/classic/package-summary.html).
+    ///
+    /// Query template:
+    /// ```cypher
+    /// CALL {
+    ///     CALL db.index.vector.queryNodes($index_name, $num_candidates, $queryVector)
+    ///     YIELD node, score
+    ///     WITH collect({node: node, score: score}) AS rows, max(score) AS max_score
+    ///     UNWIND rows AS row
+    ///     RETURN row.node AS node, CASE WHEN max_score > 0 THEN row.score / max_score ELSE 0.0 END AS vector_score, 0.0 AS fulltext_score
+    ///     UNION ALL
+    ///     CALL db.index.fulltext.queryNodes($fulltext_index_name, $queryText, {limit: $num_candidates})
+    ///     YIELD node, score
+    ///     WITH collect({node: node, score: score}) AS rows, max(score) AS max_score
+    ///     UNWIND rows AS row
+    ///     RETURN row.node AS node, 0.0 AS vector_score, CASE WHEN max_score > 0 THEN row.score / max_score ELSE 0.0 END AS fulltext_score
+    /// }
+    /// WITH node, max(vector_score) AS vector_score, max(fulltext_score) AS fulltext_score
+    /// WITH node, $alpha * vector_score + (1 - $alpha) * fulltext_score AS score
+    /// WHERE {where_clause}
+    /// RETURN score, ID(node) AS element_id, node {.*, embedding:null } AS node
+    /// ORDER BY score DESC
+    /// LIMIT $num_candidates
+    /// ```
+    pub async fn top_n_hybrid<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        query: &str,
+        n: usize,
+        fulltext_index_name: &str,
+        alpha: f64,
+    ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(VectorStoreError::DatastoreError(
+                format!("Hybrid search alpha must be between 0 and 1, got {}", alpha).into(),
+            ));
+        }
+
+        let prompt_embedding = self.embedding_model.embed_text(query).await?;
+
+        let where_clause = match &self.search_params.post_vector_search_filter {
+            Some(filter) => format!("WHERE {}", filter),
+            None => "".to_string(),
+        };
+
+        let cypher = format!(
+            "\
+            CALL {{
+                CALL db.index.vector.queryNodes($index_name, $num_candidates, $queryVector)
+                YIELD node, score
+                WITH collect({{node: node, score: score}}) AS rows, max(score) AS max_score
+                UNWIND rows AS row
+                RETURN row.node AS node, CASE WHEN max_score > 0 THEN row.score / max_score ELSE 0.0 END AS vector_score, 0.0 AS fulltext_score
+                UNION ALL
+                CALL db.index.fulltext.queryNodes($fulltext_index_name, $queryText, {{limit: $num_candidates}})
+                YIELD node, score
+                WITH collect({{node: node, score: score}}) AS rows, max(score) AS max_score
+                UNWIND rows AS row
+                RETURN row.node AS node, 0.0 AS vector_score, CASE WHEN max_score > 0 THEN row.score / max_score ELSE 0.0 END AS fulltext_score
+            }}
+            WITH node, max(vector_score) AS vector_score, max(fulltext_score) AS fulltext_score
+            WITH node, $alpha * vector_score + (1 - $alpha) * fulltext_score AS score
+            {}
+            RETURN score, ID(node) AS element_id, node {{.*, {}:null }} AS node
+            ORDER BY score DESC
+            LIMIT $num_candidates",
+            where_clause,
+            escape_identifier(&self.index_config.embedding_property)
+        );
+
+        tracing::debug!("Query before params: {}", cypher);
+
+        let query = Query::new(cypher)
+            .param("queryVector", prompt_embedding.vec)
+            .param("queryText", query)
+            .param("num_candidates", n as i64)
+            .param("alpha", alpha)
+            .param("index_name", self.index_config.index_name.clone())
+            .param("fulltext_index_name", fulltext_index_name);
+
+        let rows = Neo4jClient::execute_and_collect::<RowResultNode<T>>(&self.graph, query).await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.score, row.element_id.to_string(), row.node))
+            .collect())
+    }
+
+    /// Create a node for each embedding of the documents, with the properties of the document
+    /// and the embedding vector stored in the index's embedding property.
+    ///
+    /// The nodes are labeled with the `node_label` of the [IndexConfig], which must be set.
+    /// Documents must serialize to maps. Nodes are created in batches of 1000, each in its own transaction.
+    ///
+    /// Query template:
+    /// ```cypher
+    /// UNWIND $rows AS row
+    /// CREATE (n:`{node_label}`)
+    /// SET n += row.props, n.`{embedding_property}` = row.embedding
+    /// ```
+    pub async fn insert_documents<Doc: Serialize + Send + Sync>(
+        &self,
+        documents: Vec<(Doc, OneOrMany<Embedding>)>,
+    ) -> Result<(), VectorStoreError> {
+        let node_label = self.index_config.node_label.as_ref().ok_or_else(|| {
+            VectorStoreError::DatastoreError(
+                format!(
+                    "A node label must be set in the config of index `{}` to insert documents",
+                    self.index_config.index_name
+                )
+                .into(),
+            )
+        })?;
+
+        let query = format!(
+            "UNWIND $rows AS row
+            CREATE (n:{})
+            SET n += row.props, n.{} = row.embedding",
+            escape_identifier(node_label),
+            escape_identifier(&self.index_config.embedding_property)
+        );
+
+        let rows = documents
+            .iter()
+            .map(|(doc, embeddings)| {
+                let props = serde_json::to_value(doc)?;
+                if !props.is_object() {
+                    return Err(VectorStoreError::DatastoreError(
+                        "Documents must serialize to a map of node properties".into(),
+                    ));
+                }
+
+                Ok(embeddings
+                    .iter()
+                    .map(|embedding| {
+                        serde_json::json!({
+                            "props": props,
+                            "embedding": embedding.vec,
+                        })
+                        .to_bolt_type()
+                    })
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        for chunk in rows.chunks(INSERT_BATCH_SIZE) {
+            tracing::debug!("Inserting {} nodes with label {}", chunk.len(), node_label);
+
+            let mut txn = self.graph.start_txn().await.map_err(neo4j_to_rig_error)?;
+            txn.run(Query::new(query.clone()).param("rows", chunk.to_vec()))
+                .await
+                .map_err(neo4j_to_rig_error)?;
+            txn.commit().await.map_err(neo4j_to_rig_error)?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Search parameters for a vector search. Neo4j currently only supports post-vector-search filtering.
@@ -199,6 +458,14 @@ pub struct RowResultNode<T> {
     node: T,
 }
 
+#[derive(Debug, Deserialize)]
+struct RowResultRelations<T, R> {
+    score: f64,
+    element_id: i64,
+    node: T,
+    related: Vec<R>,
+}
+
 #[derive(Debug, Deserialize)]
 struct RowResult {
     score: f64,
@@ -11,12 +11,12 @@ use rig::{
     providers::openai,
     Embed, OneOrMany,
 };
-use rig_neo4j::{vector_index::SearchParams, Neo4jClient, ToBoltType};
+use rig_neo4j::{vector_index::SearchParams, IndexState, Neo4jClient, ToBoltType};
 
 const BOLT_PORT: u16 = 7687;
 const HTTP_PORT: u16 = 7474;
 
-#[derive(Embed, Clone, serde::Deserialize, Debug)]
+#[derive(Embed, Clone, serde::Deserialize, serde::Serialize, Debug)]
 struct Word {
     id: String,
     #[embed]
@@ -112,7 +112,7 @@ async fn vector_search_test() {
     // Create a vector index on our vector store
     // IMPORTANT: Reuse the same model that was used to generate the embeddings
     let index = neo4j_client
-        .get_index(model, "vector_index", SearchParams::default())
+        .get_index(model.clone(), "vector_index", SearchParams::default())
         .await
         .unwrap();
 
@@ -131,7 +131,116 @@ async fn vector_search_test() {
             "document": "Definition of a *glarb-glarb*: A glarb-glarb is a ancient tool used by the ancestors of the inhabitants of planet Jiro to farm the land.",
             "embedding": serde_json::Value::Null
         })
-    )
+    );
+
+    // Insert a new document through the index, which uses the label of the indexed nodes
+    let embeddings = EmbeddingsBuilder::new(model)
+        .document(Word {
+            id: "doc3".to_string(),
+            definition: "Definition of a *zindle*: A zindle is a musical instrument made of hollow moon rocks.".to_string(),
+        })
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+    index.insert_documents(embeddings).await.unwrap();
+
+    let results = index
+        .top_n::<serde_json::Value>("What is a zindle?", 1)
+        .await
+        .unwrap();
+
+    let (_, _, value) = &results.first().unwrap();
+    assert_eq!(value["id"], "doc3");
+
+    // Fetch related nodes along with the vector search results
+    neo4j_client
+        .graph
+        .run(neo4rs::query(
+            "MATCH (d:DocumentEmbeddings {id: 'doc1'})
+            CREATE (d)-[:USED_BY]->(:Species {name: 'Jiro inhabitants'})",
+        ))
+        .await
+        .unwrap();
+
+    let results = index
+        .top_n_with_relations::<serde_json::Value, serde_json::Value>(
+            "What is a glarb?",
+            1,
+            "OPTIONAL MATCH (n)-[:USED_BY]->(related:Species)",
+        )
+        .await
+        .unwrap();
+
+    let (_, _, value, related) = &results.first().unwrap();
+    assert_eq!(value["id"], "doc1");
+    assert_eq!(
+        related,
+        &vec![serde_json::json!({"name": "Jiro inhabitants"})]
+    );
+
+    // Hybrid search: "zindle" is matched exactly by the full-text index.
+    // doc3 was inserted from a `Word`, so its text is in the `definition` property
+    neo4j_client
+        .graph
+        .run(neo4rs::query(
+            "CREATE FULLTEXT INDEX fulltext_index IF NOT EXISTS
+                FOR (m:DocumentEmbeddings) ON EACH [m.document, m.definition]",
+        ))
+        .await
+        .unwrap();
+    neo4j_client
+        .graph
+        .run(neo4rs::query("CALL db.awaitIndex('fulltext_index')"))
+        .await
+        .unwrap();
+
+    let results = index
+        .top_n_hybrid::<serde_json::Value>("zindle", 2, "fulltext_index", 0.5)
+        .await
+        .unwrap();
+
+    let (score, _, value) = &results.first().unwrap();
+    assert_eq!(value["id"], "doc3");
+    assert!(*score > 0.5);
+    assert!(results.windows(2).all(|w| w[0].0 >= w[1].0));
+
+    // Full-text scores only
+    let results = index
+        .top_n_hybrid::<serde_json::Value>("glarb", 3, "fulltext_index", 0.0)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results.first().unwrap().2["id"], "doc1");
+    assert_eq!(results.first().unwrap().0, 1.0);
+
+    assert!(index
+        .top_n_hybrid::<serde_json::Value>("zindle", 1, "fulltext_index", 1.5)
+        .await
+        .is_err());
+
+    assert_eq!(
+        neo4j_client.index_status("vector_index").await.unwrap(),
+        IndexState::Online
+    );
+    assert_eq!(
+        neo4j_client.list_vector_indexes().await.unwrap(),
+        vec!["vector_index".to_string()]
+    );
+    neo4j_client
+        .drop_vector_index("vector_index")
+        .await
+        .unwrap();
+    // Dropping a missing index is not an error
+    neo4j_client
+        .drop_vector_index("vector_index")
+        .await
+        .unwrap();
+    assert!(neo4j_client.list_vector_indexes().await.unwrap().is_empty());
+    assert_eq!(
+        neo4j_client.index_status("vector_index").await.unwrap(),
+        IndexState::NotFound
+    );
 }
 
 async fn create_embeddings(model: openai::EmbeddingModel) -> Vec<(Word, OneOrMany<Embedding>)> {
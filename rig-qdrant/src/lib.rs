@@ -1,12 +1,23 @@
+use std::collections::HashMap;
+
 use qdrant_client::{
-    qdrant::{point_id::PointIdOptions, PointId, Query, QueryPoints},
-    Qdrant,
+    qdrant::{
+        point_id::PointIdOptions, vectors_config, Condition, CountPointsBuilder, Distance,
+        GetPointsBuilder, PointId, PointStruct, PointsIdsList, Query, QueryBatchPoints,
+        QueryPoints, ScoredPoint, SetPayloadPointsBuilder, UpsertPointsBuilder, VectorInput,
+        Vectors,
+    },
+    Payload, Qdrant,
 };
 use rig::{
-    embeddings::EmbeddingModel,
-    vector_store::{VectorStoreError, VectorStoreIndex},
+    embeddings::{Embedding, EmbeddingModel},
+    vector_store::{Score, VectorStoreError, VectorStoreIndex},
+    OneOrMany,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+
+/// Default number of points upserted per request by [QdrantVectorStore::insert_documents].
+pub const DEFAULT_BATCH_SIZE: usize = 256;
 
 /// Represents a vector store implementation using Qdrant - <https://qdrant.tech/> as the backend.
 pub struct QdrantVectorStore<M: EmbeddingModel> {
@@ -16,6 +27,12 @@ pub struct QdrantVectorStore<M: EmbeddingModel> {
     client: Qdrant,
     /// Default search parameters
     query_params: QueryPoints,
+    /// Maximum number of points upserted per request when inserting documents
+    batch_size: usize,
+    /// Payload field holding the document. The whole payload is the document if `None`.
+    document_field: Option<String>,
+    /// Distance metric of the collection, which determines the direction of scores and thresholds
+    distance: Distance,
 }
 
 impl<M: EmbeddingModel> QdrantVectorStore<M> {
@@ -31,16 +48,392 @@ impl<M: EmbeddingModel> QdrantVectorStore<M> {
             client,
             model,
             query_params,
+            batch_size: DEFAULT_BATCH_SIZE,
+            document_field: None,
+            distance: Distance::Cosine,
+        }
+    }
+
+    /// Declare the distance metric of the collection (default: [Distance::Cosine]).
+    ///
+    /// With [Distance::Cosine] and [Distance::Dot], scores are similarities (higher is better), while
+    /// with [Distance::Euclid] and [Distance::Manhattan], scores are distances (lower is better).
+    /// See also [QdrantVectorStore::detect_distance].
+    pub fn distance(mut self, distance: Distance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    /// Set the distance metric from the configuration of the collection of the default query parameters
+    /// (of the vector named by `using`, if set).
+    pub async fn detect_distance(mut self) -> Result<Self, VectorStoreError> {
+        let info = self
+            .client
+            .collection_info(&self.query_params.collection_name)
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        let params = match info
+            .result
+            .and_then(|info| info.config)
+            .and_then(|config| config.params)
+            .and_then(|params| params.vectors_config)
+            .and_then(|vectors_config| vectors_config.config)
+        {
+            Some(vectors_config::Config::Params(params)) => Some(params),
+            Some(vectors_config::Config::ParamsMap(mut params_map)) => self
+                .query_params
+                .using
+                .as_ref()
+                .and_then(|name| params_map.map.remove(name)),
+            None => None,
+        };
+
+        self.distance = params
+            .and_then(|params| Distance::try_from(params.distance).ok())
+            .ok_or_else(|| {
+                VectorStoreError::DatastoreError(
+                    format!(
+                        "Could not find the distance metric of collection {}",
+                        self.query_params.collection_name
+                    )
+                    .into(),
+                )
+            })?;
+
+        Ok(self)
+    }
+
+    /// Only return results with a score at least as good as `threshold`. Qdrant applies the
+    /// threshold in the direction of the distance metric of the collection: for [Distance::Cosine]
+    /// and [Distance::Dot], results with a lower score are dropped, while for [Distance::Euclid] and
+    /// [Distance::Manhattan], results with a higher score (i.e.: further away) are dropped.
+    ///
+    /// Searches fail if the threshold would filter out every result for the declared distance
+    /// metric (e.g.: a negative threshold with [Distance::Euclid]), as this usually means that the
+    /// threshold was meant for another metric. See [QdrantVectorStore::distance]. Sparse searches
+    /// are not checked, see [QdrantVectorStore::top_n_sparse].
+    pub fn score_threshold(mut self, threshold: f32) -> Self {
+        self.query_params.score_threshold = Some(threshold);
+        self
+    }
+
+    /// Check that the score threshold (if any) makes sense for the distance metric of the collection.
+    fn check_score_threshold(&self) -> Result<(), VectorStoreError> {
+        let Some(threshold) = self.query_params.score_threshold else {
+            return Ok(());
+        };
+
+        let valid = match self.distance {
+            Distance::Cosine => threshold <= 1.0,
+            Distance::Euclid | Distance::Manhattan => threshold >= 0.0,
+            Distance::Dot | Distance::UnknownDistance => true,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(VectorStoreError::DatastoreError(
+                format!(
+                    "Score threshold {} filters out all results with the {} distance",
+                    threshold,
+                    self.distance.as_str_name()
+                )
+                .into(),
+            ))
         }
     }
 
+    /// Store documents in the payload field `field` instead of using the whole payload as the document,
+    /// e.g.: to keep auxiliary fields (timestamps, source, etc.) next to the document in the payload.
+    ///
+    /// Search results are then deserialized from `payload[field]`, and [QdrantVectorStore::insert_documents]
+    /// nests the serialized documents under `field`.
+    pub fn document_field(mut self, field: impl Into<String>) -> Self {
+        self.document_field = Some(field.into());
+        self
+    }
+
+    /// Set the maximum number of points upserted per request by [QdrantVectorStore::insert_documents].
+    /// Defaults to [DEFAULT_BATCH_SIZE].
+    ///
+    /// Panics if `batch_size` is 0.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be greater than 0");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Insert documents and their embeddings in the collection of the default query parameters.
+    /// Each embedding of a document is stored as a separate point, with a random UUID as id and
+    /// the serialized document (which must be a JSON object) as payload.
+    ///
+    /// Points are upserted in batches of at most `batch_size` points (see [QdrantVectorStore::batch_size]).
+    /// If a vector name is set (see [QdrantVectorStore::vector_name]), the embeddings are stored under that name.
+    pub async fn insert_documents<Doc: Serialize + Send + Sync>(
+        &self,
+        documents: Vec<(Doc, OneOrMany<Embedding>)>,
+    ) -> Result<(), VectorStoreError> {
+        let mut points = Vec::with_capacity(self.batch_size);
+
+        for (doc, embeddings) in documents {
+            let doc = serde_json::to_value(&doc)?;
+            let payload = Payload::try_from(match self.document_field {
+                Some(ref field) => serde_json::json!({ field: doc }),
+                None => doc,
+            })
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+            for embedding in embeddings.into_iter() {
+                let vector: Vec<f32> = embedding.vec.iter().map(|&x| x as f32).collect();
+                let vectors: Vectors = match self.query_params.using {
+                    Some(ref name) => [(name.clone(), vector)]
+                        .into_iter()
+                        .collect::<HashMap<_, _>>()
+                        .into(),
+                    None => vector.into(),
+                };
+
+                points.push(PointStruct::new(
+                    uuid::Uuid::new_v4().to_string(),
+                    vectors,
+                    payload.clone(),
+                ));
+
+                if points.len() == self.batch_size {
+                    self.upsert_points(std::mem::take(&mut points)).await?;
+                }
+            }
+        }
+
+        if !points.is_empty() {
+            self.upsert_points(points).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Upsert a batch of points in the collection of the default query parameters.
+    async fn upsert_points(&self, points: Vec<PointStruct>) -> Result<(), VectorStoreError> {
+        self.client
+            .upsert_points(
+                UpsertPointsBuilder::new(&self.query_params.collection_name, points).wait(true),
+            )
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        Ok(())
+    }
+
+    /// Exclude the points with the given ids from the search results, e.g.: to avoid returning
+    /// documents that were already retrieved earlier in a conversation.
+    /// The ids are added as a `must_not` condition to the filter of the query parameters.
+    pub fn exclude_ids(mut self, ids: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        let mut filter = self.query_params.filter.take().unwrap_or_default();
+        filter.must_not.push(Condition::has_id(
+            ids.into_iter().map(|id| point_id(id.as_ref())),
+        ));
+        self.query_params.filter = Some(filter);
+        self
+    }
+
+    /// Query the named vector `vector_name` instead of the default vector of the collection,
+    /// e.g.: the dense vector of a collection that also holds sparse vectors for hybrid search.
+    /// Sets the `using` field of the query parameters.
+    pub fn vector_name(mut self, vector_name: impl Into<String>) -> Self {
+        self.query_params.using = Some(vector_name.into());
+        self
+    }
+
+    /// Search for the top `n` nearest neighbors to the given sparse vector, given as parallel lists of
+    /// `indices` and `values`, within the named sparse vector `vector_name`.
+    /// Returns a vector of tuples containing the score, ID, and document of the nearest neighbors.
+    ///
+    /// The sparse vector is computed by the caller (e.g.: with BM25 or SPLADE), not by the embedding model of the store.
+    ///
+    /// Qdrant always scores sparse vectors by dot product, so the score threshold (if any) is applied
+    /// as is, without being checked against the distance metric of the collection.
+    pub async fn top_n_sparse<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        vector_name: &str,
+        indices: Vec<u32>,
+        values: Vec<f32>,
+        n: usize,
+    ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
+        let query = Query::new_nearest(VectorInput::new_sparse(indices, values));
+
+        let mut params = self.prepare_query_params(Some(query), n);
+        params.using = Some(vector_name.to_string());
+
+        let result = self
+            .client
+            .query(params)
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        result
+            .result
+            .into_iter()
+            .map(|point| {
+                let (score, id, doc) = self.scored_point_result(point)?;
+                Ok((score, id.to_string(), doc))
+            })
+            .collect()
+    }
+
+    /// Converts a `ScoredPoint` to a tuple of score, typed ID and deserialized document.
+    fn scored_point_result<T: for<'a> Deserialize<'a>>(
+        &self,
+        point: ScoredPoint,
+    ) -> Result<(f64, QdrantPointId, T), VectorStoreError> {
+        let id = typed_id(point.id)?;
+        let score = point.score as f64;
+        Ok((score, id, self.payload_document(point.payload)?))
+    }
+
+    /// Search for the top `n` nearest neighbors to the given query, using the query of the
+    /// default query parameters if set, or the embedding of `query` otherwise.
+    async fn search(&self, query: &str, n: usize) -> Result<Vec<ScoredPoint>, VectorStoreError> {
+        self.check_score_threshold()?;
+
+        let query = match self.query_params.query {
+            Some(ref q) => Some(q.clone()),
+            None => Some(Query::new_nearest(self.generate_query_vector(query).await?)),
+        };
+
+        let params = self.prepare_query_params(query, n);
+        let result = self
+            .client
+            .query(params)
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        Ok(result.result)
+    }
+
+    /// Search for the top `n` nearest neighbors to each of the given queries, in a single batch
+    /// query request. The queries are embedded in as few calls to the embedding model as possible.
+    async fn search_batch(
+        &self,
+        queries: &[&str],
+        n: usize,
+    ) -> Result<Vec<Vec<ScoredPoint>>, VectorStoreError> {
+        self.check_score_threshold()?;
+
+        let query_points = match self.query_params.query {
+            Some(ref q) => vec![self.prepare_query_params(Some(q.clone()), n); queries.len()],
+            None => {
+                let mut query_points = Vec::with_capacity(queries.len());
+                for chunk in queries.chunks(M::MAX_DOCUMENTS) {
+                    let embeddings = self
+                        .model
+                        .embed_texts(chunk.iter().map(|query| query.to_string()))
+                        .await?;
+
+                    query_points.extend(embeddings.into_iter().map(|embedding| {
+                        let vector = embedding.vec.iter().map(|&x| x as f32).collect::<Vec<_>>();
+                        self.prepare_query_params(Some(Query::new_nearest(vector)), n)
+                    }));
+                }
+                query_points
+            }
+        };
+
+        let result = self
+            .client
+            .query_batch(QueryBatchPoints {
+                collection_name: self.query_params.collection_name.clone(),
+                query_points,
+                read_consistency: self.query_params.read_consistency,
+                timeout: self.query_params.timeout,
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        Ok(result
+            .result
+            .into_iter()
+            .map(|batch| batch.result)
+            .collect())
+    }
+
+    /// Same as [VectorStoreIndex::top_n], but returns the ids of the points as [QdrantPointId]s
+    /// instead of strings, so that numeric and UUID ids can be told apart without re-parsing them.
+    pub async fn top_n_with_typed_ids<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> Result<Vec<(f64, QdrantPointId, T)>, VectorStoreError> {
+        self.search(query, n)
+            .await?
+            .into_iter()
+            .map(|point| self.scored_point_result(point))
+            .collect()
+    }
+
+    /// Deserialize the document from a point payload: either the whole payload or
+    /// the document field of the payload, if set.
+    fn payload_document<T: for<'a> Deserialize<'a>>(
+        &self,
+        payload: HashMap<String, qdrant_client::qdrant::Value>,
+    ) -> Result<T, VectorStoreError> {
+        let mut payload = serde_json::to_value(payload)?;
+
+        let document = match self.document_field {
+            Some(ref field) => payload
+                .get_mut(field)
+                .map(serde_json::Value::take)
+                .ok_or_else(|| {
+                    VectorStoreError::DatastoreError(
+                        format!("Missing document field `{}` in point payload", field).into(),
+                    )
+                })?,
+            None => payload,
+        };
+
+        Ok(serde_json::from_value(document)?)
+    }
+
     /// Embed query based on `QdrantVectorStore` model and modify the vector in the required format.
     async fn generate_query_vector(&self, query: &str) -> Result<Vec<f32>, VectorStoreError> {
         let embedding = self.model.embed_text(query).await?;
         Ok(embedding.vec.iter().map(|&x| x as f32).collect())
     }
 
+    /// Update the payload of the point with the given id, without re-embedding it.
+    /// The fields of `patch` (which must be a JSON object) are merged into the existing payload
+    /// using Qdrant's [set payload](https://qdrant.tech/documentation/concepts/payload/#set-payload) operation.
+    /// If a document field is set (see [QdrantVectorStore::document_field]), they are merged into that field
+    /// instead of the root of the payload, so that the update is visible in the returned documents.
+    ///
+    /// The point is looked up in the collection of the default query parameters.
+    pub async fn update_metadata(
+        &self,
+        id: &str,
+        patch: serde_json::Value,
+    ) -> Result<(), VectorStoreError> {
+        let payload =
+            Payload::try_from(patch).map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        let mut request = SetPayloadPointsBuilder::new(&self.query_params.collection_name, payload)
+            .points_selector(PointsIdsList {
+                ids: vec![point_id(id)],
+            })
+            .wait(true);
+        if let Some(ref field) = self.document_field {
+            request = request.key(field);
+        }
+
+        self.client
+            .set_payload(request)
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        Ok(())
+    }
+
     /// Fill in query parameters with the given query and limit.
+    /// Other fields, such as the filter or the vector name (`using`), are kept as is.
     fn prepare_query_params(&self, query: Option<Query>, limit: usize) -> QueryPoints {
         let mut params = self.query_params.clone();
         params.query = query;
@@ -49,50 +442,83 @@ impl<M: EmbeddingModel> QdrantVectorStore<M> {
     }
 }
 
-/// Converts a `PointId` to its string representation.
-fn stringify_id(id: PointId) -> Result<String, VectorStoreError> {
-    match id.point_id_options {
-        Some(PointIdOptions::Num(num)) => Ok(num.to_string()),
-        Some(PointIdOptions::Uuid(uuid)) => Ok(uuid.to_string()),
-        None => Err(VectorStoreError::DatastoreError(
-            "Invalid point ID format".into(),
-        )),
+/// Typed id of a Qdrant point. Qdrant ids are either unsigned integers or UUIDs.
+///
+/// Its string representation (see [stringify_id]) is the one used for the ids returned by
+/// [VectorStoreIndex::top_n] and [VectorStoreIndex::top_n_ids].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QdrantPointId {
+    Numeric(u64),
+    Uuid(String),
+}
+
+impl std::fmt::Display for QdrantPointId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QdrantPointId::Numeric(num) => write!(f, "{}", num),
+            QdrantPointId::Uuid(uuid) => write!(f, "{}", uuid),
+        }
+    }
+}
+
+impl TryFrom<PointId> for QdrantPointId {
+    type Error = VectorStoreError;
+
+    fn try_from(id: PointId) -> Result<Self, Self::Error> {
+        match id.point_id_options {
+            Some(PointIdOptions::Num(num)) => Ok(QdrantPointId::Numeric(num)),
+            Some(PointIdOptions::Uuid(uuid)) => Ok(QdrantPointId::Uuid(uuid)),
+            None => Err(VectorStoreError::DatastoreError(
+                "Invalid point ID format".into(),
+            )),
+        }
+    }
+}
+
+impl From<QdrantPointId> for PointId {
+    fn from(id: QdrantPointId) -> Self {
+        match id {
+            QdrantPointId::Numeric(num) => num.into(),
+            QdrantPointId::Uuid(uuid) => uuid.into(),
+        }
+    }
+}
+
+/// Converts a `PointId` to its string representation: the decimal representation of numeric ids,
+/// or the UUID itself.
+pub fn stringify_id(id: PointId) -> Result<String, VectorStoreError> {
+    QdrantPointId::try_from(id).map(|id| id.to_string())
+}
+
+/// Converts the optional id of a point returned by Qdrant to a [QdrantPointId].
+fn typed_id(id: Option<PointId>) -> Result<QdrantPointId, VectorStoreError> {
+    id.ok_or_else(|| VectorStoreError::DatastoreError("Missing point ID".into()))?
+        .try_into()
+}
+
+/// Converts a string id to a `PointId`.
+/// Qdrant ids are either unsigned integers or UUIDs.
+fn point_id(id: &str) -> PointId {
+    match id.parse::<u64>() {
+        Ok(num) => num.into(),
+        Err(_) => id.into(),
     }
 }
 
 impl<M: EmbeddingModel + std::marker::Sync + Send> VectorStoreIndex for QdrantVectorStore<M> {
     /// Search for the top `n` nearest neighbors to the given query within the Qdrant vector store.
-    /// Returns a vector of tuples containing the score, ID, and payload of the nearest neighbors.
+    /// Returns a vector of tuples containing the score, ID, and document of the nearest neighbors.
     async fn top_n<T: for<'a> Deserialize<'a> + Send>(
         &self,
         query: &str,
         n: usize,
     ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
-        let query = match self.query_params.query {
-            Some(ref q) => Some(q.clone()),
-            None => Some(Query::new_nearest(self.generate_query_vector(query).await?)),
-        };
-
-        let params = self.prepare_query_params(query, n);
-        let result = self
-            .client
-            .query(params)
-            .await
-            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
-
-        result
-            .result
+        Ok(self
+            .top_n_with_typed_ids(query, n)
+            .await?
             .into_iter()
-            .map(|item| {
-                let id =
-                    stringify_id(item.id.ok_or_else(|| {
-                        VectorStoreError::DatastoreError("Missing point ID".into())
-                    })?)?;
-                let score = item.score as f64;
-                let payload = serde_json::from_value(serde_json::to_value(item.payload)?)?;
-                Ok((score, id, payload))
-            })
-            .collect()
+            .map(|(score, id, doc)| (score, id.to_string(), doc))
+            .collect())
     }
 
     /// Search for the top `n` nearest neighbors to the given query within the Qdrant vector store.
@@ -102,28 +528,74 @@ impl<M: EmbeddingModel + std::marker::Sync + Send> VectorStoreIndex for QdrantVe
         query: &str,
         n: usize,
     ) -> Result<Vec<(f64, String)>, VectorStoreError> {
-        let query = match self.query_params.query {
-            Some(ref q) => Some(q.clone()),
-            None => Some(Query::new_nearest(self.generate_query_vector(query).await?)),
-        };
+        self.search(query, n)
+            .await?
+            .into_iter()
+            .map(|point| Ok((point.score as f64, typed_id(point.id)?.to_string())))
+            .collect()
+    }
 
-        let params = self.prepare_query_params(query, n);
-        let points = self
+    /// Search for the top `n` nearest neighbors to each of the given queries, using Qdrant's
+    /// batch query API, so that all the searches are done in a single request.
+    async fn top_n_multi<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        queries: &[&str],
+        n: usize,
+    ) -> Result<Vec<Vec<(f64, String, T)>>, VectorStoreError> {
+        self.search_batch(queries, n)
+            .await?
+            .into_iter()
+            .map(|points| {
+                points
+                    .into_iter()
+                    .map(|point| {
+                        let (score, id, doc) = self.scored_point_result(point)?;
+                        Ok((score, id.to_string(), doc))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Scores are distances with the [Distance::Euclid] and [Distance::Manhattan] distance metrics,
+    /// and similarities otherwise (see [QdrantVectorStore::distance]).
+    fn score(&self, value: f64) -> Score {
+        match self.distance {
+            Distance::Euclid | Distance::Manhattan => Score::Distance(value),
+            _ => Score::Similarity(value),
+        }
+    }
+
+    /// Count the points in the collection of the default query parameters, using an exact count.
+    async fn count(&self) -> Result<u64, VectorStoreError> {
+        let result = self
             .client
-            .query(params)
+            .count(CountPointsBuilder::new(&self.query_params.collection_name).exact(true))
             .await
-            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?
-            .result;
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
 
-        points
+        Ok(result.result.map(|result| result.count).unwrap_or_default())
+    }
+
+    /// Get the document of the point with the given id, in the collection of the default query parameters.
+    async fn get_by_id<T: for<'a> Deserialize<'a> + Send>(
+        &self,
+        id: &str,
+    ) -> Result<Option<T>, VectorStoreError> {
+        let result = self
+            .client
+            .get_points(
+                GetPointsBuilder::new(&self.query_params.collection_name, vec![point_id(id)])
+                    .with_payload(true),
+            )
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        result
+            .result
             .into_iter()
-            .map(|point| {
-                let id =
-                    stringify_id(point.id.ok_or_else(|| {
-                        VectorStoreError::DatastoreError("Missing point ID".into())
-                    })?)?;
-                Ok((point.score as f64, id))
-            })
-            .collect()
+            .next()
+            .map(|point| self.payload_document(point.payload))
+            .transpose()
     }
 }
@@ -1,24 +1,32 @@
 use testcontainers::{
     core::{IntoContainerPort, WaitFor},
     runners::AsyncRunner,
-    GenericImage,
+    ContainerAsync, GenericImage,
 };
 
 use qdrant_client::{
     qdrant::{
-        CreateCollectionBuilder, Distance, PointStruct, QueryPointsBuilder, UpsertPointsBuilder,
-        VectorParamsBuilder,
+        CreateCollectionBuilder, Distance, NamedVectors, PointStruct, QueryPointsBuilder,
+        SparseVectorParamsBuilder, SparseVectorsConfigBuilder, UpsertPointsBuilder, Vector,
+        VectorParamsBuilder, VectorsConfigBuilder,
     },
     Payload, Qdrant,
 };
 use rig::{
-    embeddings::EmbeddingsBuilder, providers::openai, vector_store::VectorStoreIndex, Embed,
+    embeddings::{Embedding, EmbeddingError, EmbeddingModel, EmbeddingsBuilder},
+    providers::openai,
+    vector_store::{Score, VectorStoreIndex},
+    Embed,
 };
-use rig_qdrant::QdrantVectorStore;
+use rig_qdrant::{QdrantPointId, QdrantVectorStore};
 
 const QDRANT_PORT: u16 = 6333;
 const QDRANT_PORT_SECONDARY: u16 = 6334;
 const COLLECTION_NAME: &str = "rig-collection";
+const HYBRID_COLLECTION_NAME: &str = "rig-hybrid-collection";
+const BATCH_COLLECTION_NAME: &str = "rig-batch-collection";
+const DOCUMENT_FIELD_COLLECTION_NAME: &str = "rig-document-field-collection";
+const EUCLID_COLLECTION_NAME: &str = "rig-euclid-collection";
 
 #[derive(Embed, Clone, serde::Deserialize, serde::Serialize, Debug)]
 struct Word {
@@ -27,9 +35,9 @@ struct Word {
     definition: String,
 }
 
-#[tokio::test]
-async fn vector_search_test() {
-    // Setup a local qdrant container for testing. NOTE: docker service must be running.
+/// Start a local qdrant container and connect to it. NOTE: docker service must be running.
+/// The container is stopped when the returned handle is dropped.
+async fn start_qdrant() -> (ContainerAsync<GenericImage>, Qdrant) {
     let container = GenericImage::new("qdrant/qdrant", "latest")
         .with_wait_for(WaitFor::Duration {
             length: std::time::Duration::from_secs(5),
@@ -50,6 +58,13 @@ async fn vector_search_test() {
         .build()
         .unwrap();
 
+    (container, client)
+}
+
+#[tokio::test]
+async fn vector_search_test() {
+    let (_container, client) = start_qdrant().await;
+
     // Create a collection with 1536 dimensions if it doesn't exist
     // Note: Make sure the dimensions match the size of the embeddings returned by the
     // model you are using
@@ -76,7 +91,49 @@ async fn vector_search_test() {
         .unwrap();
 
     let query_params = QueryPointsBuilder::new(COLLECTION_NAME).with_payload(true);
-    let vector_store = QdrantVectorStore::new(client, model, query_params.build());
+    let vector_store = QdrantVectorStore::new(client.clone(), model.clone(), query_params.build());
+
+    assert_eq!(vector_store.count().await.unwrap(), 3);
+
+    let word = vector_store
+        .get_by_id::<serde_json::Value>("62a36d43-80b6-4fd6-990c-f75bb02287d1")
+        .await
+        .unwrap();
+    assert_eq!(word.unwrap()["id"], "62a36d43-80b6-4fd6-990c-f75bb02287d1");
+    assert!(vector_store
+        .get_by_id::<serde_json::Value>("00000000-0000-0000-0000-000000000000")
+        .await
+        .unwrap()
+        .is_none());
+
+    // Excluded ids never appear in the results, even when they would rank first
+    let excluding_store = QdrantVectorStore::new(
+        client,
+        model,
+        QueryPointsBuilder::new(COLLECTION_NAME)
+            .with_payload(true)
+            .build(),
+    )
+    .exclude_ids(["f9e17d59-32e5-440c-be02-b2759a654824"]);
+
+    let results = excluding_store
+        .top_n_ids("What is a linglingdong?", 3)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 2);
+    assert!(results
+        .iter()
+        .all(|(_, id)| id != "f9e17d59-32e5-440c-be02-b2759a654824"));
+
+    let results = vector_store
+        .top_n_with_typed_ids::<serde_json::Value>("What is a linglingdong?", 1)
+        .await
+        .unwrap();
+    assert_eq!(
+        results.first().unwrap().1,
+        QdrantPointId::Uuid("f9e17d59-32e5-440c-be02-b2759a654824".to_string())
+    );
 
     let results = vector_store
         .top_n::<serde_json::Value>("What is a linglingdong?", 1)
@@ -91,7 +148,347 @@ async fn vector_search_test() {
             "definition": "Definition of a *linglingdong*: A term used by inhabitants of the far side of the moon to describe humans.",
             "id": "f9e17d59-32e5-440c-be02-b2759a654824"
         })
+    );
+
+    let multi_results = vector_store
+        .top_n_multi::<serde_json::Value>(&["What is a linglingdong?", "What is a glarb-glarb?"], 1)
+        .await
+        .unwrap();
+    assert_eq!(multi_results.len(), 2);
+    assert_eq!(
+        multi_results[0].first().unwrap().1,
+        "f9e17d59-32e5-440c-be02-b2759a654824"
+    );
+    assert!(multi_results.iter().all(|result| result.len() == 1));
+
+    // Update the point payload without re-embedding it
+    let (score, id, _) = &results.first().unwrap();
+    vector_store
+        .update_metadata(id, serde_json::json!({"status": "archived"}))
+        .await
+        .unwrap();
+
+    let results = vector_store
+        .top_n::<serde_json::Value>("What is a linglingdong?", 1)
+        .await
+        .unwrap();
+
+    let (new_score, new_id, value) = &results.first().unwrap();
+
+    assert_eq!(new_score, score);
+    assert_eq!(new_id, id);
+    assert_eq!(
+        value,
+        &serde_json::json!({
+            "definition": "Definition of a *linglingdong*: A term used by inhabitants of the far side of the moon to describe humans.",
+            "id": "f9e17d59-32e5-440c-be02-b2759a654824",
+            "status": "archived"
+        })
+    )
+}
+
+#[tokio::test]
+async fn euclid_score_threshold_test() {
+    let (_container, client) = start_qdrant().await;
+
+    client
+        .create_collection(
+            CreateCollectionBuilder::new(EUCLID_COLLECTION_NAME)
+                .vectors_config(VectorParamsBuilder::new(2, Distance::Euclid)),
+        )
+        .await
+        .unwrap();
+
+    // MockEmbeddingModel embeds queries as [1.0, 0.0]: "near" is at distance 0, "far" at distance 3
+    client
+        .upsert_points(
+            UpsertPointsBuilder::new(
+                EUCLID_COLLECTION_NAME,
+                vec![
+                    PointStruct::new(
+                        0,
+                        vec![1.0, 0.0],
+                        Payload::try_from(serde_json::json!({"id": "near"})).unwrap(),
+                    ),
+                    PointStruct::new(
+                        1,
+                        vec![4.0, 0.0],
+                        Payload::try_from(serde_json::json!({"id": "far"})).unwrap(),
+                    ),
+                ],
+            )
+            .wait(true),
+        )
+        .await
+        .unwrap();
+
+    let vector_store = QdrantVectorStore::new(
+        client,
+        MockEmbeddingModel,
+        QueryPointsBuilder::new(EUCLID_COLLECTION_NAME)
+            .with_payload(true)
+            .build(),
+    )
+    .detect_distance()
+    .await
+    .unwrap();
+
+    let results = vector_store
+        .top_n_scored::<serde_json::Value>("query", 2)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].2["id"], "near");
+    assert_eq!(results[1].0, Score::Distance(3.0));
+    assert!(results[0].0.is_better_than(&results[1].0));
+
+    // With a distance metric, the threshold is a maximum distance
+    let vector_store = vector_store.score_threshold(1.0);
+    let results = vector_store
+        .top_n::<serde_json::Value>("query", 2)
+        .await
+        .unwrap();
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].2["id"], "near");
+
+    // A negative distance threshold would silently filter out everything
+    let vector_store = vector_store.score_threshold(-0.5);
+    assert!(vector_store.top_n_ids("query", 2).await.is_err());
+}
+
+/// Embedding model returning the same 2-dimensional vector for every text.
+#[derive(Clone)]
+struct MockEmbeddingModel;
+
+impl EmbeddingModel for MockEmbeddingModel {
+    const MAX_DOCUMENTS: usize = 16;
+
+    fn ndims(&self) -> usize {
+        2
+    }
+
+    async fn embed_texts(
+        &self,
+        documents: impl IntoIterator<Item = String> + Send,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        Ok(documents
+            .into_iter()
+            .map(|document| Embedding {
+                document,
+                vec: vec![1.0, 0.0],
+            })
+            .collect())
+    }
+}
+
+#[tokio::test]
+async fn named_and_sparse_vector_test() {
+    let (_container, client) = start_qdrant().await;
+
+    let mut vectors_config = VectorsConfigBuilder::default();
+    vectors_config.add_named_vector_params("dense", VectorParamsBuilder::new(2, Distance::Cosine));
+    let mut sparse_vectors_config = SparseVectorsConfigBuilder::default();
+    sparse_vectors_config.add_named_vector_params("sparse", SparseVectorParamsBuilder::default());
+
+    client
+        .create_collection(
+            CreateCollectionBuilder::new(HYBRID_COLLECTION_NAME)
+                .vectors_config(vectors_config)
+                .sparse_vectors_config(sparse_vectors_config),
+        )
+        .await
+        .unwrap();
+
+    // The "dense" point is closest to the dense query vector, the "sparse" point to the sparse query
+    let points = [
+        (1, vec![1.0, 0.0], (vec![1], vec![0.1])),
+        (2, vec![0.0, 1.0], (vec![7], vec![1.0])),
+    ]
+    .into_iter()
+    .map(|(id, dense, (indices, values))| {
+        PointStruct::new(
+            id,
+            NamedVectors::default()
+                .add_vector("dense", dense)
+                .add_vector("sparse", Vector::new_sparse(indices, values)),
+            Payload::try_from(serde_json::json!({ "name": format!("point{id}") })).unwrap(),
+        )
+    })
+    .collect::<Vec<_>>();
+
+    client
+        .upsert_points(UpsertPointsBuilder::new(HYBRID_COLLECTION_NAME, points).wait(true))
+        .await
+        .unwrap();
+
+    let vector_store = QdrantVectorStore::new(
+        client,
+        MockEmbeddingModel,
+        QueryPointsBuilder::new(HYBRID_COLLECTION_NAME)
+            .with_payload(true)
+            .build(),
+    )
+    .vector_name("dense");
+
+    let results = vector_store.top_n_ids("query", 1).await.unwrap();
+    assert_eq!(results.first().unwrap().1, "1");
+
+    let results = vector_store
+        .top_n_with_typed_ids::<serde_json::Value>("query", 1)
+        .await
+        .unwrap();
+    assert_eq!(results.first().unwrap().1, QdrantPointId::Numeric(1));
+
+    let results = vector_store
+        .top_n_sparse::<serde_json::Value>("sparse", vec![7], vec![1.0], 1)
+        .await
+        .unwrap();
+    let (_, id, value) = results.first().unwrap();
+    assert_eq!(id, "2");
+    assert_eq!(value, &serde_json::json!({ "name": "point2" }));
+}
+
+#[tokio::test]
+async fn insert_documents_test() {
+    let (_container, client) = start_qdrant().await;
+
+    client
+        .create_collection(
+            CreateCollectionBuilder::new(BATCH_COLLECTION_NAME)
+                .vectors_config(VectorParamsBuilder::new(2, Distance::Cosine)),
+        )
+        .await
+        .unwrap();
+
+    let words = (0..5).map(|i| Word {
+        id: i.to_string(),
+        definition: format!("Definition of word {i}"),
+    });
+
+    let documents = EmbeddingsBuilder::new(MockEmbeddingModel)
+        .documents(words)
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+
+    // 5 points in batches of 2: the last, partial, batch must be flushed too
+    let vector_store = QdrantVectorStore::new(
+        client,
+        MockEmbeddingModel,
+        QueryPointsBuilder::new(BATCH_COLLECTION_NAME)
+            .with_payload(true)
+            .build(),
+    )
+    .batch_size(2);
+
+    vector_store.insert_documents(documents).await.unwrap();
+
+    assert_eq!(vector_store.count().await.unwrap(), 5);
+
+    let results = vector_store
+        .top_n::<Word>("Definition of word", 5)
+        .await
+        .unwrap();
+    let mut ids = results
+        .into_iter()
+        .map(|(_, _, word)| word.id)
+        .collect::<Vec<_>>();
+    ids.sort();
+    assert_eq!(ids, vec!["0", "1", "2", "3", "4"]);
+}
+
+#[tokio::test]
+async fn document_field_test() {
+    let (_container, client) = start_qdrant().await;
+
+    client
+        .create_collection(
+            CreateCollectionBuilder::new(DOCUMENT_FIELD_COLLECTION_NAME)
+                .vectors_config(VectorParamsBuilder::new(2, Distance::Cosine)),
+        )
+        .await
+        .unwrap();
+
+    let documents = EmbeddingsBuilder::new(MockEmbeddingModel)
+        .document(Word {
+            id: "doc0".to_string(),
+            definition: "Definition of a *flurbo*".to_string(),
+        })
+        .unwrap()
+        .build()
+        .await
+        .unwrap();
+
+    let vector_store = QdrantVectorStore::new(
+        client.clone(),
+        MockEmbeddingModel,
+        QueryPointsBuilder::new(DOCUMENT_FIELD_COLLECTION_NAME)
+            .with_payload(true)
+            .build(),
     )
+    .document_field("document");
+
+    vector_store.insert_documents(documents).await.unwrap();
+
+    // Metadata updates are merged into the document field
+    let (_, id) = vector_store
+        .top_n_ids("flurbo", 1)
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+    vector_store
+        .update_metadata(&id, serde_json::json!({"source": "wiki"}))
+        .await
+        .unwrap();
+
+    let document = vector_store
+        .get_by_id::<serde_json::Value>(&id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        document,
+        serde_json::json!({
+            "id": "doc0",
+            "definition": "Definition of a *flurbo*",
+            "source": "wiki"
+        })
+    );
+
+    let (_, _, word) = vector_store
+        .top_n::<Word>("flurbo", 1)
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(word.id, "doc0");
+
+    // The whole payload is the document if no document field is set
+    let raw_store = QdrantVectorStore::new(
+        client,
+        MockEmbeddingModel,
+        QueryPointsBuilder::new(DOCUMENT_FIELD_COLLECTION_NAME)
+            .with_payload(true)
+            .build(),
+    );
+    let (_, _, payload) = raw_store
+        .top_n::<serde_json::Value>("flurbo", 1)
+        .await
+        .unwrap()
+        .pop()
+        .unwrap();
+    assert_eq!(
+        payload,
+        serde_json::json!({
+            "document": {
+                "id": "doc0",
+                "definition": "Definition of a *flurbo*",
+                "source": "wiki"
+            }
+        })
+    );
 }
 
 async fn create_points(model: openai::EmbeddingModel) -> Vec<PointStruct> {
@@ -1,8 +1,12 @@
 use rig::embeddings::{Embedding, EmbeddingModel};
-use rig::vector_store::{VectorStoreError, VectorStoreIndex};
+use rig::vector_store::{check_duplicate_ids, Score, VectorStoreError, VectorStoreIndex};
 use rig::OneOrMany;
+use rusqlite::{DatabaseName, OpenFlags, OptionalExtension};
 use serde::Deserialize;
 use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use tokio_rusqlite::Connection;
 use tracing::{debug, info};
 use zerocopy::IntoBytes;
@@ -17,6 +21,11 @@ pub enum SqliteError {
 pub trait ColumnValue: Send + Sync {
     fn to_sql_string(&self) -> String;
     fn column_type(&self) -> &'static str;
+
+    /// The value bound to the insert statement. Defaults to the text returned by `to_sql_string`.
+    fn to_sql_value(&self) -> rusqlite::types::Value {
+        rusqlite::types::Value::Text(self.to_sql_string())
+    }
 }
 
 pub struct Column {
@@ -87,10 +96,18 @@ pub trait SqliteVectorStoreTable: Send + Sync + Clone {
 #[derive(Clone)]
 pub struct SqliteVectorStore<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> {
     conn: Connection,
+    /// Connections used for reads, in round-robin. Empty if reads go through `conn`.
+    readers: Vec<Connection>,
+    next_reader: Arc<AtomicUsize>,
     _phantom: PhantomData<(E, T)>,
 }
 
 impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVectorStore<E, T> {
+    /// Create the document, embeddings and metadata tables of the store if they do not exist.
+    ///
+    /// The embedding dimensions of `embedding_model` are recorded in the `{table}_metadata` table
+    /// when the store is first created. An error is returned if the store is later opened with
+    /// an embedding model producing embeddings of different dimensions.
     pub async fn new(conn: Connection, embedding_model: &E) -> Result<Self, VectorStoreError> {
         let dims = embedding_model.ndims();
         let table_name = T::name();
@@ -127,51 +144,152 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVec
             }
         }
 
-        conn.call(move |conn| {
-            conn.execute_batch("BEGIN")?;
+        let stored_dims = conn
+            .call(move |conn| {
+                conn.execute_batch("BEGIN")?;
 
-            // Create document table
-            conn.execute_batch(&create_table)?;
+                // Create document table
+                conn.execute_batch(&create_table)?;
 
-            // Create indexes
-            for index_stmt in create_indexes {
-                conn.execute_batch(&index_stmt)?;
-            }
+                // Create indexes
+                for index_stmt in create_indexes {
+                    conn.execute_batch(&index_stmt)?;
+                }
 
-            // Create embeddings table
-            conn.execute_batch(&format!(
-                "CREATE VIRTUAL TABLE IF NOT EXISTS {}_embeddings USING vec0(embedding float[{}])",
-                table_name, dims
-            ))?;
+                // Create embeddings table
+                conn.execute_batch(&format!(
+                    "CREATE VIRTUAL TABLE IF NOT EXISTS {}_embeddings USING vec0(embedding float[{}])",
+                    table_name, dims
+                ))?;
 
-            conn.execute_batch("COMMIT")?;
-            Ok(())
-        })
-        .await
-        .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+                // Create metadata table and record the embedding dimensions the store was created with
+                conn.execute_batch(&format!(
+                    "CREATE TABLE IF NOT EXISTS {}_metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL)",
+                    table_name
+                ))?;
+                conn.execute(
+                    &format!(
+                        "INSERT OR IGNORE INTO {}_metadata (key, value) VALUES ('ndims', ?1)",
+                        table_name
+                    ),
+                    rusqlite::params![dims.to_string()],
+                )?;
+                let stored_dims: String = conn.query_row(
+                    &format!(
+                        "SELECT value FROM {}_metadata WHERE key = 'ndims'",
+                        table_name
+                    ),
+                    [],
+                    |row| row.get(0),
+                )?;
+
+                if stored_dims == dims.to_string() {
+                    conn.execute_batch("COMMIT")?;
+                } else {
+                    conn.execute_batch("ROLLBACK")?;
+                }
+                Ok(stored_dims)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        if stored_dims != dims.to_string() {
+            return Err(VectorStoreError::DatastoreError(
+                format!(
+                    "Embedding model dimensions ({}) do not match the dimensions of table {} ({})",
+                    dims, table_name, stored_dims
+                )
+                .into(),
+            ));
+        }
 
         Ok(Self {
             conn,
+            readers: Vec::new(),
+            next_reader: Arc::new(AtomicUsize::new(0)),
             _phantom: PhantomData,
         })
     }
 
+    /// Create the store with a dedicated write connection and a pool of read connections.
+    /// Searches are spread across the `readers` in round-robin while all writes go through `writer`.
+    ///
+    /// All connections must be opened on the same database file (in-memory databases are not
+    /// shared between connections) with the `sqlite-vec` extension loaded. The database should
+    /// use WAL journaling (`PRAGMA journal_mode=WAL`) so that readers do not block on the writer
+    /// and vice versa.
+    pub async fn with_pool(
+        writer: Connection,
+        readers: Vec<Connection>,
+        embedding_model: &E,
+    ) -> Result<Self, VectorStoreError> {
+        let mut store = Self::new(writer, embedding_model).await?;
+        store.readers = readers;
+        Ok(store)
+    }
+
+    /// Connection to use for the next read.
+    fn read_conn(&self) -> &Connection {
+        if self.readers.is_empty() {
+            return &self.conn;
+        }
+
+        let i = self.next_reader.fetch_add(1, Ordering::Relaxed);
+        &self.readers[i % self.readers.len()]
+    }
+
     pub fn index(self, model: E) -> SqliteVectorIndex<E, T> {
         SqliteVectorIndex::new(model, self)
     }
 
+    /// Create an index over the store which ranks documents using the given distance metric.
+    pub fn index_with_metric(
+        self,
+        model: E,
+        metric: SqliteDistanceMetric,
+    ) -> SqliteVectorIndex<E, T> {
+        SqliteVectorIndex::new(model, self).distance_metric(metric)
+    }
+
     pub fn add_rows_with_txn(
         &self,
         txn: &rusqlite::Transaction<'_>,
         documents: Vec<(T, OneOrMany<Embedding>)>,
     ) -> Result<i64, tokio_rusqlite::Error> {
+        let rowids = self.add_rows_returning_ids_with_txn(txn, documents)?;
+        Ok(rowids.last().copied().unwrap_or(0))
+    }
+
+    /// Insert (or replace) the documents and their embeddings using the given transaction.
+    /// Returns the rowid assigned to each document, in the same order as `documents`.
+    ///
+    /// Returns an error wrapping a [VectorStoreError::DuplicateIdsError] without writing anything
+    /// if the batch contains the same document id more than once.
+    pub fn add_rows_returning_ids_with_txn(
+        &self,
+        txn: &rusqlite::Transaction<'_>,
+        documents: Vec<(T, OneOrMany<Embedding>)>,
+    ) -> Result<Vec<i64>, tokio_rusqlite::Error> {
+        check_duplicate_ids(documents.iter().map(|(doc, _)| doc.id()))
+            .map_err(|e| tokio_rusqlite::Error::Other(Box::new(e)))?;
+
         info!("Adding {} documents to store", documents.len());
         let table_name = T::name();
-        let mut last_id = 0;
+        let mut rowids = Vec::with_capacity(documents.len());
 
         for (doc, embeddings) in &documents {
             debug!("Storing document with id {}", doc.id());
 
+            // Remove the embeddings of the document being replaced, if any, so that they
+            // are not orphaned or conflict with the embeddings inserted below.
+            txn.execute(
+                &format!(
+                    "DELETE FROM {0}_embeddings WHERE rowid IN (SELECT rowid FROM {0} WHERE id = ?1)",
+                    table_name
+                ),
+                [doc.id()],
+            )?;
+
             let values = doc.column_values();
             let columns = values.iter().map(|(col, _)| *col).collect::<Vec<_>>();
 
@@ -188,9 +306,10 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVec
 
             txn.execute(
                 &insert_sql,
-                rusqlite::params_from_iter(values.iter().map(|(_, val)| val.to_sql_string())),
+                rusqlite::params_from_iter(values.iter().map(|(_, val)| val.to_sql_value())),
             )?;
-            last_id = txn.last_insert_rowid();
+            let last_id = txn.last_insert_rowid();
+            rowids.push(last_id);
 
             let embeddings_sql = format!(
                 "INSERT INTO {}_embeddings (rowid, embedding) VALUES (?1, ?2)",
@@ -211,25 +330,248 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVec
             }
         }
 
-        Ok(last_id)
+        Ok(rowids)
+    }
+
+    /// Delete the documents with the given ids, along with their embeddings, in a single transaction.
+    /// Ids that do not exist are skipped. Returns the number of deleted documents.
+    pub async fn delete_by_ids(&self, ids: &[String]) -> Result<usize, VectorStoreError> {
+        let table_name = T::name();
+        let ids = ids.to_vec();
+
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                let mut deleted = 0;
+
+                for id in &ids {
+                    let rowid: Option<i64> = match tx.query_row(
+                        &format!("SELECT rowid FROM {} WHERE id = ?1", table_name),
+                        [id],
+                        |row| row.get(0),
+                    ) {
+                        Ok(rowid) => Some(rowid),
+                        Err(rusqlite::Error::QueryReturnedNoRows) => None,
+                        Err(e) => return Err(e.into()),
+                    };
+
+                    if let Some(rowid) = rowid {
+                        debug!("Deleting document with id {}", id);
+                        // Delete the embeddings first so that the rowid still resolves to the document
+                        tx.execute(
+                            &format!("DELETE FROM {}_embeddings WHERE rowid = ?1", table_name),
+                            [rowid],
+                        )?;
+                        deleted += tx.execute(
+                            &format!("DELETE FROM {} WHERE rowid = ?1", table_name),
+                            [rowid],
+                        )?;
+                    }
+                }
+
+                tx.commit()?;
+                Ok(deleted)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
     }
 
+    /// Delete all documents and embeddings of the store in a single transaction.
+    /// Returns the number of deleted documents.
+    pub async fn delete_all(&self) -> Result<usize, VectorStoreError> {
+        let table_name = T::name();
+
+        self.conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                tx.execute(&format!("DELETE FROM {}_embeddings", table_name), [])?;
+                let deleted = tx.execute(&format!("DELETE FROM {}", table_name), [])?;
+                tx.commit()?;
+                Ok(deleted)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+    }
+
+    /// Get up to `limit` documents matching a SQL `filter`, without similarity ranking.
+    /// Returns the id and deserialized document of each match.
+    ///
+    /// `filter` is the `WHERE` clause of a query on the document table, e.g.: `"category = ?1"`,
+    /// and `params` are the values bound to its parameters.
+    ///
+    /// ❗ The filter is inserted verbatim in the SQL query. Never build it from untrusted input,
+    /// pass untrusted values as `params` instead.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let news = vector_store
+    ///     .find_by_filter::<Document>("category = ?1", vec!["news".into()], 10)
+    ///     .await?;
+    /// ```
+    pub async fn find_by_filter<D: for<'a> Deserialize<'a>>(
+        &self,
+        filter: &str,
+        params: Vec<rusqlite::types::Value>,
+        limit: usize,
+    ) -> Result<Vec<(String, D)>, VectorStoreError> {
+        let table_name = T::name();
+        let columns = T::schema();
+        let column_names: Vec<&str> = columns.iter().map(|column| column.name).collect();
+        let query = format!(
+            "SELECT {} FROM {} WHERE {} LIMIT {}",
+            column_names.join(", "),
+            table_name,
+            filter,
+            limit
+        );
+
+        let rows = self
+            .read_conn()
+            .call(move |conn| {
+                let mut stmt = conn.prepare(&query)?;
+                let rows = stmt
+                    .query_map(rusqlite::params_from_iter(params), |row| {
+                        let id: String = row.get(0)?; // Assuming id is always first column
+                        Ok((id, row_to_json(row, &column_names)?))
+                    })?
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(rows)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        debug!("Found {} documents matching filter", rows.len());
+        rows.into_iter()
+            .map(|(id, doc)| Ok((id, serde_json::from_value(doc)?)))
+            .collect()
+    }
+
+    /// Checkpoint the write-ahead log (if the database uses WAL journaling) so that all committed
+    /// writes are persisted to the main database file.
+    pub async fn flush(&self) -> Result<(), VectorStoreError> {
+        self.conn
+            .call(|conn| {
+                conn.query_row("PRAGMA wal_checkpoint(FULL)", [], |_| Ok(()))?;
+                Ok(())
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+    }
+
+    /// Flush pending writes and close the underlying connections.
+    /// Note: the connections are shared with all clones of the store, which can no longer be used afterwards.
+    pub async fn close(self) -> Result<(), VectorStoreError> {
+        self.flush().await?;
+        for reader in self.readers {
+            reader
+                .close()
+                .await
+                .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+        }
+        self.conn
+            .close()
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+    }
+
+    /// Copy the whole database of the store (documents, embeddings and metadata) to the file at
+    /// `path`, using SQLite's online backup API. The file is overwritten if it exists.
+    ///
+    /// This is typically used to persist a store built in memory (`Connection::open(":memory:")`).
+    pub async fn backup_to(&self, path: &Path) -> Result<(), VectorStoreError> {
+        let path = path.to_path_buf();
+
+        self.conn
+            .call(move |conn| {
+                conn.backup(DatabaseName::Main, path, None)?;
+                Ok(())
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+    }
+
+    /// Replace the whole database of the store with the database at `path`, e.g.: a snapshot
+    /// created with [SqliteVectorStore::backup_to], using SQLite's online backup API.
+    ///
+    /// Returns an error without restoring anything if the embeddings of the snapshot do not have
+    /// the same dimensions as the embeddings of the store.
+    pub async fn restore_from(&self, path: &Path) -> Result<(), VectorStoreError> {
+        let table_name = T::name();
+        let path = path.to_path_buf();
+        let ndims_query = format!(
+            "SELECT value FROM {}_metadata WHERE key = 'ndims'",
+            table_name
+        );
+
+        let mismatch = self
+            .conn
+            .call(move |conn| {
+                let dims: String = conn.query_row(&ndims_query, [], |row| row.get(0))?;
+                let snapshot_dims: String =
+                    rusqlite::Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)?
+                        .query_row(&ndims_query, [], |row| row.get(0))?;
+
+                if dims != snapshot_dims {
+                    return Ok(Some((dims, snapshot_dims)));
+                }
+
+                conn.restore(
+                    DatabaseName::Main,
+                    path,
+                    None::<fn(rusqlite::backup::Progress)>,
+                )?;
+                Ok(None)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        match mismatch {
+            Some((dims, snapshot_dims)) => Err(VectorStoreError::DatastoreError(
+                format!(
+                    "Embedding dimensions of the store ({}) do not match the dimensions of the snapshot ({})",
+                    dims, snapshot_dims
+                )
+                .into(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Add documents and their embeddings to the store in a single transaction.
+    /// Returns an error without writing anything if the batch contains the same document id more than once.
     pub async fn add_rows(
         &self,
         documents: Vec<(T, OneOrMany<Embedding>)>,
     ) -> Result<i64, VectorStoreError> {
+        let rowids = self.add_rows_returning_ids(documents).await?;
+        Ok(rowids.last().copied().unwrap_or(0))
+    }
+
+    /// Insert (or replace) the documents and their embeddings in a single transaction.
+    /// Returns the rowid assigned to each document, in the same order as `documents`.
+    pub async fn add_rows_returning_ids(
+        &self,
+        documents: Vec<(T, OneOrMany<Embedding>)>,
+    ) -> Result<Vec<i64>, VectorStoreError> {
         let documents = documents.clone();
         let this = self.clone();
 
         self.conn
             .call(move |conn| {
                 let tx = conn.transaction().map_err(tokio_rusqlite::Error::from)?;
-                let result = this.add_rows_with_txn(&tx, documents)?;
+                let result = this.add_rows_returning_ids_with_txn(&tx, documents)?;
                 tx.commit().map_err(tokio_rusqlite::Error::from)?;
                 Ok(result)
             })
             .await
-            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+            .map_err(|e| match e {
+                // Errors of the store itself (e.g.: duplicate ids) are returned as is
+                tokio_rusqlite::Error::Other(e) => match e.downcast::<VectorStoreError>() {
+                    Ok(e) => *e,
+                    Err(e) => VectorStoreError::DatastoreError(e),
+                },
+                e => VectorStoreError::DatastoreError(Box::new(e)),
+            })
     }
 }
 
@@ -318,6 +660,48 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> SqliteVec
 pub struct SqliteVectorIndex<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable + 'static> {
     store: SqliteVectorStore<E, T>,
     embedding_model: E,
+    distance_metric: SqliteDistanceMetric,
+}
+
+/// Distance metric used to rank documents in a [SqliteVectorIndex].
+/// The scores returned by the index are distances, i.e.: lower is better.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SqliteDistanceMetric {
+    /// Euclidean distance. Searches use the `vec0` KNN index.
+    #[default]
+    L2,
+    /// Cosine distance (`1 - cosine similarity`), computed with `vec_distance_cosine`.
+    Cosine,
+    /// Manhattan distance, computed with `vec_distance_l1`.
+    L1,
+}
+
+impl SqliteDistanceMetric {
+    /// Build the query selecting `select_cols` and the distance of the `?2` documents closest to `?1`.
+    fn search_query(&self, select_cols: &str, table_name: &str) -> String {
+        match self {
+            SqliteDistanceMetric::L2 => format!(
+                "SELECT {select_cols}, e.distance
+                FROM {table_name}_embeddings e
+                JOIN {table_name} d ON e.rowid = d.rowid
+                WHERE e.embedding MATCH ?1 AND k = ?2
+                ORDER BY e.distance"
+            ),
+            SqliteDistanceMetric::Cosine | SqliteDistanceMetric::L1 => {
+                let function = match self {
+                    SqliteDistanceMetric::Cosine => "vec_distance_cosine",
+                    _ => "vec_distance_l1",
+                };
+                format!(
+                    "SELECT {select_cols}, {function}(e.embedding, ?1) AS distance
+                    FROM {table_name}_embeddings e
+                    JOIN {table_name} d ON e.rowid = d.rowid
+                    ORDER BY distance
+                    LIMIT ?2"
+                )
+            }
+        }
+    }
 }
 
 impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable> SqliteVectorIndex<E, T> {
@@ -325,8 +709,15 @@ impl<E: EmbeddingModel + 'static, T: SqliteVectorStoreTable> SqliteVectorIndex<E
         Self {
             store,
             embedding_model,
+            distance_metric: SqliteDistanceMetric::default(),
         }
     }
+
+    /// Set the distance metric used to rank documents (default: [SqliteDistanceMetric::L2]).
+    pub fn distance_metric(mut self, metric: SqliteDistanceMetric) -> Self {
+        self.distance_metric = metric;
+        self
+    }
 }
 
 impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorStoreIndex
@@ -345,34 +736,24 @@ impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorSto
         // Get all column names from SqliteVectorStoreTable
         let columns = T::schema();
         let column_names: Vec<&str> = columns.iter().map(|column| column.name).collect();
+        let metric = self.distance_metric;
 
         let rows = self
             .store
-            .conn
+            .read_conn()
             .call(move |conn| {
                 // Build SELECT statement with all columns
                 let select_cols = column_names.join(", ");
-                let mut stmt = conn.prepare(&format!(
-                    "SELECT d.{}, e.distance 
-                    FROM {}_embeddings e
-                    JOIN {} d ON e.rowid = d.rowid
-                    WHERE e.embedding MATCH ?1 AND k = ?2
-                    ORDER BY e.distance",
-                    select_cols, table_name, table_name
-                ))?;
+                let mut stmt =
+                    conn.prepare(&metric.search_query(&format!("d.{}", select_cols), table_name))?;
 
                 let rows = stmt
                     .query_map(rusqlite::params![query_vec.as_bytes().to_vec(), n], |row| {
-                        // Create a map of column names to values
-                        let mut map = serde_json::Map::new();
-                        for (i, col_name) in column_names.iter().enumerate() {
-                            let value: String = row.get(i)?;
-                            map.insert(col_name.to_string(), serde_json::Value::String(value));
-                        }
+                        let doc = row_to_json(row, &column_names)?;
                         let distance: f64 = row.get(column_names.len())?;
                         let id: String = row.get(0)?; // Assuming id is always first column
 
-                        Ok((id, serde_json::Value::Object(map), distance))
+                        Ok((id, doc, distance))
                     })?
                     .collect::<Result<Vec<_>, _>>()?;
                 Ok(rows)
@@ -407,19 +788,13 @@ impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorSto
         let embedding = self.embedding_model.embed_text(query).await?;
         let query_vec = serialize_embedding(&embedding);
         let table_name = T::name();
+        let metric = self.distance_metric;
 
         let results = self
             .store
-            .conn
+            .read_conn()
             .call(move |conn| {
-                let mut stmt = conn.prepare(&format!(
-                    "SELECT d.id, e.distance 
-                     FROM {0}_embeddings e
-                     JOIN {0} d ON e.rowid = d.rowid
-                     WHERE e.embedding MATCH ?1 AND k = ?2
-                     ORDER BY e.distance",
-                    table_name
-                ))?;
+                let mut stmt = conn.prepare(&metric.search_query("d.id", table_name))?;
 
                 let results = stmt
                     .query_map(
@@ -441,6 +816,87 @@ impl<E: EmbeddingModel + std::marker::Sync, T: SqliteVectorStoreTable> VectorSto
         debug!("Found {} matching document IDs", results.len());
         Ok(results)
     }
+
+    async fn count(&self) -> Result<u64, VectorStoreError> {
+        let table_name = T::name();
+
+        self.store
+            .read_conn()
+            .call(move |conn| {
+                let count: i64 =
+                    conn.query_row(&format!("SELECT count(*) FROM {}", table_name), [], |row| {
+                        row.get(0)
+                    })?;
+                Ok(count as u64)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))
+    }
+
+    async fn get_by_id<D: for<'a> Deserialize<'a> + Send>(
+        &self,
+        id: &str,
+    ) -> Result<Option<D>, VectorStoreError> {
+        let table_name = T::name();
+        let columns = T::schema();
+        let column_names: Vec<&str> = columns.iter().map(|column| column.name).collect();
+        let id = id.to_string();
+
+        let doc = self
+            .store
+            .read_conn()
+            .call(move |conn| {
+                let doc = conn
+                    .query_row(
+                        &format!(
+                            "SELECT {} FROM {} WHERE id = ?1",
+                            column_names.join(", "),
+                            table_name
+                        ),
+                        [id],
+                        |row| row_to_json(row, &column_names),
+                    )
+                    .optional()?;
+                Ok(doc)
+            })
+            .await
+            .map_err(|e| VectorStoreError::DatastoreError(Box::new(e)))?;
+
+        Ok(doc.map(serde_json::from_value).transpose()?)
+    }
+
+    /// The scores are distances, whatever the distance metric of the index.
+    fn score(&self, value: f64) -> Score {
+        Score::Distance(value)
+    }
+}
+
+/// Convert the first columns of a row, named `column_names`, to a JSON object.
+fn row_to_json(
+    row: &rusqlite::Row<'_>,
+    column_names: &[&str],
+) -> rusqlite::Result<serde_json::Value> {
+    let mut map = serde_json::Map::new();
+    for (i, col_name) in column_names.iter().enumerate() {
+        map.insert(col_name.to_string(), value_ref_to_json(row.get_ref(i)?));
+    }
+    Ok(serde_json::Value::Object(map))
+}
+
+/// Convert a SQLite value to the corresponding JSON value, according to its storage class.
+/// Blobs are converted to arrays of bytes and non-finite reals to `null`.
+fn value_ref_to_json(value: rusqlite::types::ValueRef<'_>) -> serde_json::Value {
+    use rusqlite::types::ValueRef;
+
+    match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(i) => serde_json::Value::from(i),
+        ValueRef::Real(f) => serde_json::Number::from_f64(f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        ValueRef::Text(text) => serde_json::Value::String(String::from_utf8_lossy(text).into()),
+        ValueRef::Blob(blob) => serde_json::Value::from(blob),
+    }
 }
 
 fn serialize_embedding(embedding: &Embedding) -> Vec<f32> {
@@ -457,6 +913,89 @@ impl ColumnValue for String {
     }
 }
 
+macro_rules! impl_integer_column_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ColumnValue for $ty {
+                fn to_sql_string(&self) -> String {
+                    self.to_string()
+                }
+
+                fn column_type(&self) -> &'static str {
+                    "INTEGER"
+                }
+
+                fn to_sql_value(&self) -> rusqlite::types::Value {
+                    rusqlite::types::Value::Integer(i64::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_column_value!(i64, i32);
+
+macro_rules! impl_real_column_value {
+    ($($ty:ty),*) => {
+        $(
+            impl ColumnValue for $ty {
+                fn to_sql_string(&self) -> String {
+                    self.to_string()
+                }
+
+                fn column_type(&self) -> &'static str {
+                    "REAL"
+                }
+
+                fn to_sql_value(&self) -> rusqlite::types::Value {
+                    rusqlite::types::Value::Real(f64::from(*self))
+                }
+            }
+        )*
+    };
+}
+
+impl_real_column_value!(f64, f32);
+
+/// Booleans are stored as `0` or `1`, since SQLite has no boolean storage class.
+impl ColumnValue for bool {
+    fn to_sql_string(&self) -> String {
+        (*self as i64).to_string()
+    }
+
+    fn column_type(&self) -> &'static str {
+        "INTEGER"
+    }
+
+    fn to_sql_value(&self) -> rusqlite::types::Value {
+        rusqlite::types::Value::Integer(*self as i64)
+    }
+}
+
+/// `None` is stored as `NULL`. Its column type is `"NULL"` since the inner type is unknown.
+impl<T: ColumnValue> ColumnValue for Option<T> {
+    fn to_sql_string(&self) -> String {
+        match self {
+            Some(value) => value.to_sql_string(),
+            None => "NULL".to_string(),
+        }
+    }
+
+    fn column_type(&self) -> &'static str {
+        match self {
+            Some(value) => value.column_type(),
+            None => "NULL",
+        }
+    }
+
+    fn to_sql_value(&self) -> rusqlite::types::Value {
+        match self {
+            Some(value) => value.to_sql_value(),
+            None => rusqlite::types::Value::Null,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -501,12 +1040,570 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn test_vector_search() -> Result<(), anyhow::Error> {
-        // Initialize the sqlite-vec extension
+    /// Initialize the sqlite-vec extension
+    fn init_sqlite_vec() {
         unsafe {
             sqlite3_auto_extension(Some(std::mem::transmute(sqlite3_vec_init as *const ())));
         }
+    }
+
+    #[derive(Clone)]
+    struct MockEmbeddingModel {
+        ndims: usize,
+    }
+
+    impl EmbeddingModel for MockEmbeddingModel {
+        const MAX_DOCUMENTS: usize = 16;
+
+        fn ndims(&self) -> usize {
+            self.ndims
+        }
+
+        async fn embed_texts(
+            &self,
+            documents: impl IntoIterator<Item = String> + Send,
+        ) -> Result<Vec<Embedding>, rig::embeddings::EmbeddingError> {
+            Ok(documents
+                .into_iter()
+                .map(|document| {
+                    // Documents of the form "1.0,2.0" are embedded as the given vector
+                    let vec = document
+                        .split(',')
+                        .map(|x| x.trim().parse::<f64>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .ok()
+                        .filter(|vec| vec.len() == self.ndims)
+                        .unwrap_or_else(|| vec![0.0; self.ndims]);
+
+                    Embedding { document, vec }
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ndims_mismatch() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+
+        SqliteVectorStore::<_, TestDocument>::new(conn.clone(), &MockEmbeddingModel { ndims: 3 })
+            .await?;
+
+        // Re-opening the store with the same model dimensions is fine
+        SqliteVectorStore::<_, TestDocument>::new(conn.clone(), &MockEmbeddingModel { ndims: 3 })
+            .await?;
+
+        let result =
+            SqliteVectorStore::<_, TestDocument>::new(conn, &MockEmbeddingModel { ndims: 4 }).await;
+        assert!(matches!(result, Err(VectorStoreError::DatastoreError(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_rows_duplicate_ids() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+        let model = MockEmbeddingModel { ndims: 3 };
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+
+        let documents = ["doc0", "doc1", "doc0"]
+            .into_iter()
+            .map(|id| TestDocument {
+                id: id.to_string(),
+                content: format!("Content of {id}"),
+            })
+            .collect::<Vec<_>>();
+
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(documents)?
+            .build()
+            .await?;
+
+        let result = vector_store.add_rows(embeddings.clone()).await;
+        assert!(matches!(
+            result,
+            Err(VectorStoreError::DuplicateIdsError(ids)) if ids == vec!["doc0"]
+        ));
+
+        // Inserts using a caller-provided transaction are checked too
+        let store = vector_store.clone();
+        let result = vector_store
+            .conn
+            .call(move |conn| {
+                let tx = conn.transaction()?;
+                store.add_rows_with_txn(&tx, embeddings)?;
+                tx.commit()?;
+                Ok(())
+            })
+            .await;
+        match result {
+            Err(tokio_rusqlite::Error::Other(e)) => assert!(matches!(
+                e.downcast_ref::<VectorStoreError>(),
+                Some(VectorStoreError::DuplicateIdsError(ids)) if ids == &vec!["doc0"]
+            )),
+            result => panic!("Expected a duplicate ids error, got {:?}", result),
+        }
+
+        assert_eq!(vector_store.index(model).count().await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_with_pool() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let path = std::env::temp_dir().join(format!("rig_sqlite_pool_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let writer = Connection::open(&path).await?;
+        writer
+            .call(|conn| {
+                conn.query_row("PRAGMA journal_mode=WAL", [], |_| Ok(()))?;
+                Ok(())
+            })
+            .await?;
+        let mut readers = Vec::new();
+        for _ in 0..2 {
+            readers.push(Connection::open(&path).await?);
+        }
+
+        let model = MockEmbeddingModel { ndims: 3 };
+        let vector_store =
+            SqliteVectorStore::<_, TestDocument>::with_pool(writer, readers, &model).await?;
+
+        let documents = ["doc0", "doc1"]
+            .into_iter()
+            .map(|id| TestDocument {
+                id: id.to_string(),
+                content: format!("Content of {id}"),
+            })
+            .collect::<Vec<_>>();
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(documents)?
+            .build()
+            .await?;
+        vector_store.add_rows(embeddings).await?;
+
+        // Writes are visible from every reader
+        let index = vector_store.clone().index(model);
+        for _ in 0..4 {
+            assert_eq!(index.top_n_ids("Content", 5).await?.len(), 2);
+        }
+
+        vector_store.close().await?;
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_close_and_reopen() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let path = std::env::temp_dir().join(format!("rig_sqlite_close_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let model = MockEmbeddingModel { ndims: 3 };
+        let documents = ["doc0", "doc1"]
+            .into_iter()
+            .map(|id| TestDocument {
+                id: id.to_string(),
+                content: format!("Content of {id}"),
+            })
+            .collect::<Vec<_>>();
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(documents)?
+            .build()
+            .await?;
+
+        let conn = Connection::open(&path).await?;
+        conn.call(|conn| {
+            conn.query_row("PRAGMA journal_mode = WAL", [], |_| Ok(()))?;
+            Ok(())
+        })
+        .await?;
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+        vector_store.add_rows(embeddings).await?;
+        vector_store.close().await?;
+
+        let conn = Connection::open(&path).await?;
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+        let mut ids = vector_store
+            .index(model)
+            .top_n_ids("Content", 5)
+            .await?
+            .into_iter()
+            .map(|(_, id)| id)
+            .collect::<Vec<_>>();
+        ids.sort();
+
+        assert_eq!(ids, vec!["doc0", "doc1"]);
+
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backup_and_restore() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let path =
+            std::env::temp_dir().join(format!("rig_sqlite_backup_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let model = MockEmbeddingModel { ndims: 3 };
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(vec![TestDocument {
+                id: "doc0".to_string(),
+                content: "Content of doc0".to_string(),
+            }])?
+            .build()
+            .await?;
+
+        // Build the store in memory and snapshot it
+        let conn = Connection::open(":memory:").await?;
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+        vector_store.add_rows(embeddings).await?;
+        vector_store.backup_to(&path).await?;
+
+        // Restore the snapshot in another in-memory store
+        let conn = Connection::open(":memory:").await?;
+        let restored = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+        restored.restore_from(&path).await?;
+
+        let index = restored.index(model);
+        assert_eq!(index.count().await?, 1);
+        let results = index.top_n_ids("Content", 1).await?;
+        assert_eq!(results[0].1, "doc0");
+
+        // The snapshot can be opened directly as well
+        let conn = Connection::open(&path).await?;
+        let model = MockEmbeddingModel { ndims: 3 };
+        let reopened = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+        assert_eq!(reopened.index(model).count().await?, 1);
+
+        // Snapshots with embeddings of other dimensions are rejected
+        let conn = Connection::open(":memory:").await?;
+        let other =
+            SqliteVectorStore::<_, TestDocument>::new(conn, &MockEmbeddingModel { ndims: 4 })
+                .await?;
+        assert!(other.restore_from(&path).await.is_err());
+
+        let _ = std::fs::remove_file(&path);
+        Ok(())
+    }
+
+    #[derive(Embed, Clone, Debug, Deserialize, PartialEq)]
+    struct Movie {
+        id: String,
+        #[embed]
+        title: String,
+        year: i64,
+        rating: f64,
+        sequel: Option<String>,
+    }
+
+    impl SqliteVectorStoreTable for Movie {
+        fn name() -> &'static str {
+            "movies"
+        }
+
+        fn schema() -> Vec<Column> {
+            vec![
+                Column::new("id", "TEXT PRIMARY KEY"),
+                Column::new("title", "TEXT"),
+                Column::new("year", "INTEGER"),
+                Column::new("rating", "REAL"),
+                Column::new("sequel", "TEXT"),
+            ]
+        }
+
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+
+        fn column_values(&self) -> Vec<(&'static str, Box<dyn ColumnValue>)> {
+            vec![
+                ("id", Box::new(self.id.clone())),
+                ("title", Box::new(self.title.clone())),
+                ("year", Box::new(self.year)),
+                ("rating", Box::new(self.rating)),
+                ("sequel", Box::new(self.sequel.clone())),
+            ]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_top_n_non_text_columns() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+        let model = MockEmbeddingModel { ndims: 3 };
+        let vector_store = SqliteVectorStore::<_, Movie>::new(conn, &model).await?;
+
+        let movie = Movie {
+            id: "movie0".to_string(),
+            title: "Alien".to_string(),
+            year: 1979,
+            rating: 8.5,
+            sequel: None,
+        };
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .document(movie.clone())?
+            .build()
+            .await?;
+        vector_store.add_rows(embeddings).await?;
+
+        let results = vector_store.index(model).top_n::<Movie>("Alien", 1).await?;
+
+        assert_eq!(
+            results
+                .into_iter()
+                .map(|(_, id, doc)| (id, doc))
+                .collect::<Vec<_>>(),
+            vec![("movie0".to_string(), movie)]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_add_rows_returning_ids() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+        let model = MockEmbeddingModel { ndims: 3 };
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn.clone(), &model).await?;
+
+        let embed = |ids: &[&str]| {
+            let documents = ids
+                .iter()
+                .map(|id| TestDocument {
+                    id: id.to_string(),
+                    content: format!("Content of {id}"),
+                })
+                .collect::<Vec<_>>();
+            EmbeddingsBuilder::new(model.clone())
+                .documents(documents)
+                .unwrap()
+                .build()
+        };
+
+        let documents = embed(&["doc0", "doc1", "doc2"]).await?;
+        let ids = documents
+            .iter()
+            .map(|(doc, _)| doc.id())
+            .collect::<Vec<_>>();
+        let rowids = vector_store.add_rows_returning_ids(documents).await?;
+
+        // Replacing existing documents
+        let replaced_documents = embed(&["doc2", "doc3", "doc0"]).await?;
+        let replaced_ids = replaced_documents
+            .iter()
+            .map(|(doc, _)| doc.id())
+            .collect::<Vec<_>>();
+        let replaced = vector_store
+            .add_rows_returning_ids(replaced_documents)
+            .await?;
+
+        let stored = conn
+            .call(|conn| {
+                let mut stmt = conn.prepare("SELECT id, rowid FROM test_documents")?;
+                let rows = stmt
+                    .query_map([], |row| {
+                        Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                    })?
+                    .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+                Ok(rows)
+            })
+            .await?;
+
+        assert_eq!(stored.len(), 4);
+        let doc1 = ids.iter().position(|id| id == "doc1").unwrap();
+        assert_eq!(stored["doc1"], rowids[doc1]);
+        assert_eq!(
+            replaced,
+            replaced_ids.iter().map(|id| stored[id]).collect::<Vec<_>>()
+        );
+
+        // Each document keeps a single embedding
+        let ids = vector_store.index(model).top_n_ids("Content", 10).await?;
+        assert_eq!(ids.len(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_column_values() {
+        use rusqlite::types::Value;
+
+        assert_eq!(42i32.to_sql_value(), Value::Integer(42));
+        assert_eq!(42i64.column_type(), "INTEGER");
+        assert_eq!(1.5f32.to_sql_value(), Value::Real(1.5));
+        assert_eq!(1.5f64.column_type(), "REAL");
+        assert_eq!(true.to_sql_string(), "1");
+        assert_eq!(false.to_sql_value(), Value::Integer(0));
+        assert_eq!(Some(3i64).to_sql_value(), Value::Integer(3));
+        assert_eq!(Some(3i64).column_type(), "INTEGER");
+        assert_eq!(None::<String>.to_sql_string(), "NULL");
+        assert_eq!(None::<String>.to_sql_value(), Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_delete() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+        let model = MockEmbeddingModel { ndims: 3 };
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+
+        let documents = ["doc0", "doc1", "doc2"]
+            .into_iter()
+            .map(|id| TestDocument {
+                id: id.to_string(),
+                content: format!("Content of {id}"),
+            })
+            .collect::<Vec<_>>();
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(documents)?
+            .build()
+            .await?;
+        vector_store.add_rows(embeddings).await?;
+
+        let deleted = vector_store
+            .delete_by_ids(&["doc0".to_string(), "missing".to_string()])
+            .await?;
+        assert_eq!(deleted, 1);
+
+        let index = vector_store.clone().index(model);
+        let mut ids = index
+            .top_n_ids("Content", 5)
+            .await?
+            .into_iter()
+            .map(|(_, id)| id)
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["doc1", "doc2"]);
+
+        assert_eq!(index.count().await?, 2);
+
+        let doc = index.get_by_id::<serde_json::Value>("doc1").await?;
+        assert_eq!(
+            doc,
+            Some(serde_json::json!({"id": "doc1", "content": "Content of doc1"}))
+        );
+        assert_eq!(index.get_by_id::<serde_json::Value>("doc0").await?, None);
+
+        assert_eq!(vector_store.delete_all().await?, 2);
+        assert!(index.top_n_ids("Content", 5).await?.is_empty());
+        assert_eq!(index.count().await?, 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_find_by_filter() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+        let model = MockEmbeddingModel { ndims: 3 };
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+
+        let documents = ["news0", "blog0", "news1", "news2"]
+            .into_iter()
+            .map(|id| TestDocument {
+                id: id.to_string(),
+                content: format!("Content of {id}"),
+            })
+            .collect::<Vec<_>>();
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(documents)?
+            .build()
+            .await?;
+        vector_store.add_rows(embeddings).await?;
+
+        let mut ids = vector_store
+            .find_by_filter::<TestDocument>("id LIKE ?1", vec!["news%".to_string().into()], 10)
+            .await?
+            .into_iter()
+            .map(|(id, doc)| {
+                assert_eq!(doc.content, format!("Content of {id}"));
+                id
+            })
+            .collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["news0", "news1", "news2"]);
+
+        let limited = vector_store
+            .find_by_filter::<TestDocument>("id LIKE ?1", vec!["news%".to_string().into()], 2)
+            .await?;
+        assert_eq!(limited.len(), 2);
+
+        assert!(vector_store
+            .find_by_filter::<TestDocument>("id = ?1", vec!["missing".to_string().into()], 10)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_distance_metric() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
+
+        let conn = Connection::open(":memory:").await?;
+        let model = MockEmbeddingModel { ndims: 2 };
+        let vector_store = SqliteVectorStore::<_, TestDocument>::new(conn, &model).await?;
+
+        // "far" points in the same direction as the query, "near" is closer but at an angle
+        let documents = vec![
+            TestDocument {
+                id: "far".to_string(),
+                content: "10.0,0.0".to_string(),
+            },
+            TestDocument {
+                id: "near".to_string(),
+                content: "2.0,1.0".to_string(),
+            },
+        ];
+        let embeddings = EmbeddingsBuilder::new(model.clone())
+            .documents(documents)?
+            .build()
+            .await?;
+        vector_store.add_rows(embeddings).await?;
+
+        let ids =
+            |results: Vec<(f64, String)>| results.into_iter().map(|(_, id)| id).collect::<Vec<_>>();
+
+        let l2 = vector_store.clone().index(model.clone());
+        assert_eq!(ids(l2.top_n_ids("2.0,0.0", 2).await?), vec!["near", "far"]);
+
+        let l1 = vector_store
+            .clone()
+            .index_with_metric(model.clone(), SqliteDistanceMetric::L1);
+        let results = l1.top_n_ids("2.0,0.0", 2).await?;
+        assert_eq!(results[0], (1.0, "near".to_string()));
+        assert_eq!(results[1], (8.0, "far".to_string()));
+
+        let results = l1.top_n_scored::<serde_json::Value>("2.0,0.0", 2).await?;
+        assert_eq!(results[0].0, Score::Distance(1.0));
+        assert!(results[0].0.is_better_than(&results[1].0));
+
+        let cosine = vector_store.index_with_metric(model, SqliteDistanceMetric::Cosine);
+        let results = cosine.top_n::<serde_json::Value>("2.0,0.0", 1).await?;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].1, "far");
+        assert!(results[0].0.abs() < 1e-6);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_vector_search() -> Result<(), anyhow::Error> {
+        init_sqlite_vec();
 
         // Initialize in-memory SQLite connection
         let conn = Connection::open(":memory:").await?;